@@ -0,0 +1,982 @@
+use crate::{
+    Auth, Generator, GeneratorOptions, GeneratorRegistry, HelixDBConnection,
+    HelixDBSchemaIntrospector, HelixQLSchemaParser, HelixSchema, OutputFile,
+    config::{Config, TargetConfig},
+    diff::diff_schemas,
+    error::{Error, Result},
+    snapshot::Snapshot,
+};
+use clap::{Parser, Subcommand};
+use log::{LevelFilter, error, info};
+use similar::TextDiff;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(
+    about = "Generate TypeScript types and typed client from HelixDB schema and queries using /introspect endpoint or a helixdb-cfg directory"
+)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// HelixDB URL to introspect [default: http://localhost:6969]
+    #[arg(short = 'u', long = "endpoint", global = true)]
+    endpoint: Option<String>,
+
+    /// Read schema.hx and queries.hx from this directory instead of introspecting a running instance
+    #[arg(
+        short = 's',
+        long = "schema-dir",
+        global = true,
+        conflicts_with = "endpoint"
+    )]
+    schema_dir: Option<PathBuf>,
+
+    /// Read the schema from a JSON snapshot written by `helix-ts-gen snapshot`
+    #[arg(
+        long = "from-snapshot",
+        value_name = "FILE",
+        global = true,
+        conflicts_with_all = ["endpoint", "schema_dir"]
+    )]
+    from_snapshot: Option<PathBuf>,
+
+    /// Send `Authorization: Bearer <TOKEN>` when introspecting [env: HELIX_BEARER_TOKEN]
+    #[arg(long = "bearer-token", value_name = "TOKEN", global = true)]
+    bearer_token: Option<String>,
+
+    /// API key to send when introspecting [env: HELIX_API_KEY]
+    #[arg(long = "api-key", value_name = "KEY", global = true)]
+    api_key: Option<String>,
+
+    /// Header carrying --api-key [default: x-api-key] [env: HELIX_API_KEY_HEADER]
+    #[arg(long = "api-key-header", value_name = "NAME", global = true)]
+    api_key_header: Option<String>,
+
+    /// Extra header to send when introspecting, as "Name: value"; may be repeated
+    #[arg(short = 'H', long = "header", value_name = "HEADER", value_parser = parse_header, global = true)]
+    headers: Vec<(String, String)>,
+
+    /// Output path, or `-` for stdout [default: helix-client.ts for typescript]
+    #[arg(short = 'o', long = "output-file")]
+    output_file: Option<String>,
+
+    /// Write a directory of TypeScript modules (nodes.ts, edges.ts, ..., index.ts) instead of a single file
+    #[arg(long = "out-dir", value_name = "DIR", conflicts_with = "output_file")]
+    out_dir: Option<PathBuf>,
+
+    /// With --out-dir, also put each node, vector and edge in its own file
+    #[arg(long = "per-entity", requires = "out_dir")]
+    per_entity: bool,
+
+    /// Code generation backend to use; restricts generation to this target when a config file declares several
+    #[arg(short = 't', long = "target")]
+    target: Option<String>,
+
+    /// Config file to use instead of discovering helix-ts-gen.toml from the current directory upwards
+    #[arg(short = 'c', long = "config", global = true)]
+    config: Option<PathBuf>,
+
+    /// Compare the generated client with the existing output file and exit non-zero if it is stale, without writing
+    #[arg(long = "check")]
+    check: bool,
+
+    /// Also emit Zod schemas for entities and query params, and allow createTypedClient to validate params
+    #[arg(long = "zod")]
+    zod: bool,
+
+    /// TypeScript type for integers wider than 53 bits (I64, U64, I128, U128)
+    #[arg(long = "large-integers", value_parser = ["number", "bigint", "string"])]
+    large_integers: Option<String>,
+
+    /// TypeScript type for Date properties and timestamps: the RFC 3339 string, a revived Date, or epoch milliseconds
+    #[arg(long = "dates", value_parser = ["iso-string", "date", "epoch"])]
+    dates: Option<String>,
+
+    /// Runtime of the generated TypeScript client: wrap helix-ts, or a dependency-free fetch client
+    #[arg(long = "client", value_parser = ["helix-ts", "fetch"])]
+    client: Option<String>,
+
+    /// Also emit assertUser-style functions that throw on the first field not matching its type
+    #[arg(long = "assertions")]
+    assertions: bool,
+
+    /// Spelling of properties and query parameters in generated code; the client maps them to the schema's names
+    #[arg(long = "property-naming", value_parser = ["preserve", "snake", "camel", "pascal"])]
+    property_naming: Option<String>,
+
+    /// Emit a distinct branded ID type per entity (UserId, KnowsId) in TypeScript
+    #[arg(long = "branded-ids")]
+    branded_ids: bool,
+
+    /// Keep running and regenerate the output whenever the schema changes
+    #[arg(short = 'w', long = "watch", conflicts_with = "check")]
+    watch: bool,
+
+    /// Log more detail (-v: requests and parsed files, -vv: raw responses)
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count, global = true, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only log errors
+    #[arg(short = 'q', long = "quiet", global = true)]
+    quiet: bool,
+
+    /// Seconds between schema polls in watch mode
+    #[arg(long = "watch-interval", default_value_t = 2, requires = "watch")]
+    watch_interval: u64,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Save the normalized schema as JSON, to generate from later with --from-snapshot
+    Snapshot {
+        /// Snapshot file to write
+        #[arg(long = "out", value_name = "FILE")]
+        out: PathBuf,
+    },
+    /// Compare two schema versions and exit non-zero if the change breaks existing clients
+    Diff {
+        /// Old schema: an http(s) URL, a helixdb-cfg directory or a snapshot file
+        old: String,
+        /// New schema, in the same forms
+        new: String,
+        /// Print the changes as JSON
+        #[arg(long = "json")]
+        json: bool,
+    },
+}
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:6969";
+
+/// `--output-file` value that writes to stdout.
+const STDOUT: &str = "-";
+
+/// One backend run: which generator, and where it writes.
+struct Job {
+    target: String,
+    generator: Box<dyn Generator>,
+    options: GeneratorOptions,
+}
+
+enum SchemaSource {
+    Dir(HelixQLSchemaParser),
+    Snapshot(PathBuf),
+    Endpoint(HelixDBSchemaIntrospector),
+}
+
+impl SchemaSource {
+    /// CLI flags take precedence over the config file.
+    fn new(args: &Args, config: &Config) -> Self {
+        let (schema_dir, snapshot, endpoint) =
+            if args.endpoint.is_some() || args.schema_dir.is_some() || args.from_snapshot.is_some()
+            {
+                (&args.schema_dir, &args.from_snapshot, &args.endpoint)
+            } else {
+                (&config.schema_dir, &config.snapshot, &config.endpoint)
+            };
+
+        if let Some(schema_dir) = schema_dir {
+            info!("Parsing schema from: {}", schema_dir.display());
+            return Self::Dir(HelixQLSchemaParser::new(schema_dir));
+        }
+        if let Some(snapshot) = snapshot {
+            info!("Reading schema snapshot: {}", snapshot.display());
+            return Self::Snapshot(snapshot.clone());
+        }
+
+        let endpoint = endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT);
+        info!("Connecting to HelixDB at: {endpoint}");
+
+        let connection = HelixDBConnection::new(endpoint).with_auth(auth(args, config));
+        Self::Endpoint(HelixDBSchemaIntrospector::new(connection))
+    }
+
+    /// A source given on the command line: a URL is introspected, a
+    /// directory parsed, and anything else read as a snapshot.
+    fn from_spec(spec: &str, args: &Args, config: &Config) -> Self {
+        if spec.starts_with("http://") || spec.starts_with("https://") {
+            let connection = HelixDBConnection::new(spec).with_auth(auth(args, config));
+            Self::Endpoint(HelixDBSchemaIntrospector::new(connection))
+        } else if Path::new(spec).is_dir() {
+            Self::Dir(HelixQLSchemaParser::new(spec))
+        } else {
+            Self::Snapshot(PathBuf::from(spec))
+        }
+    }
+
+    async fn load(&self) -> Result<HelixSchema> {
+        match self {
+            Self::Dir(parser) => parser.parse_schema(),
+            Self::Snapshot(path) => Ok(Snapshot::load(path)?.schema),
+            Self::Endpoint(introspector) => introspector.introspect_schema().await,
+        }
+    }
+}
+
+/// Auth from the config file, then environment variables, then CLI flags.
+fn auth(args: &Args, config: &Config) -> Auth {
+    config.auth.clone().merge(Auth::from_env()).merge(Auth {
+        bearer_token: args.bearer_token.clone(),
+        api_key: args.api_key.clone(),
+        api_key_header: args.api_key_header.clone(),
+        headers: args.headers.iter().cloned().collect(),
+    })
+}
+
+fn parse_header(header: &str) -> std::result::Result<(String, String), String> {
+    let (name, value) = header
+        .split_once(':')
+        .ok_or_else(|| format!("expected \"Name: value\", got \"{header}\""))?;
+    Ok((name.trim().to_string(), value.trim().to_string()))
+}
+
+/// Parses the process arguments and runs the command line with the targets of
+/// `registry`, reporting errors on stderr.
+///
+/// ```no_run
+/// use helix_ts_gen::GeneratorRegistry;
+///
+/// #[tokio::main]
+/// async fn main() -> std::process::ExitCode {
+///     helix_ts_gen::cli::run(GeneratorRegistry::default()).await
+/// }
+/// ```
+pub async fn run(registry: GeneratorRegistry) -> ExitCode {
+    let args = Args::parse();
+    init_logging(&args);
+
+    match execute(args, &registry).await {
+        Ok(code) => code,
+        Err(e) => {
+            report(&e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Logs go to stderr, so that stdout only carries output (`--output-file -`,
+/// `--check` diffs, `diff` results). `RUST_LOG` overrides the flags.
+fn init_logging(args: &Args) {
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Info,
+        (false, 1) => LevelFilter::Debug,
+        (false, _) => LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(LevelFilter::Warn)
+        .filter_module("helix_ts_gen", level)
+        .format(|buf, record| match record.level() {
+            log::Level::Info => writeln!(buf, "{}", record.args()),
+            level => writeln!(buf, "{}: {}", level.as_str().to_lowercase(), record.args()),
+        })
+        .parse_default_env()
+        .init();
+}
+
+/// Prints an error with its causes and, where there is an obvious next step,
+/// a hint.
+fn report(error: &Error) {
+    let message = error.to_string();
+    eprintln!("error: {message}");
+
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        if !message.contains(&cause_message) {
+            eprintln!("  caused by: {cause_message}");
+        }
+        source = cause.source();
+    }
+
+    let hint = match error {
+        Error::Connection { .. } => Some(
+            "is HelixDB running there? Use --endpoint to point elsewhere, or --schema-dir / --from-snapshot to generate without a database",
+        ),
+        Error::Status { status, .. } if status.as_u16() == 401 || status.as_u16() == 403 => {
+            Some("pass credentials with --api-key, --bearer-token or --header (or HELIX_API_KEY)")
+        }
+        Error::Status { status, .. } if status.as_u16() == 404 => {
+            Some("the instance has no /introspect endpoint; check --endpoint or use --schema-dir")
+        }
+        Error::UnknownType { .. } => Some("declare the type in your schema, or fix the reference"),
+        Error::NameCollision { .. } => Some(
+            "rename one of them in the schema; for fields, another --property-naming may also do",
+        ),
+        _ => None,
+    };
+    if let Some(hint) = hint {
+        eprintln!("  hint: {hint}");
+    }
+}
+
+async fn execute(args: Args, registry: &GeneratorRegistry) -> Result<ExitCode> {
+    let config = load_config(&args)?;
+
+    if let Some(Command::Snapshot { out }) = &args.command {
+        let schema = SchemaSource::new(&args, &config).load().await?;
+        write_output(&[OutputFile {
+            path: out.clone(),
+            contents: Snapshot::new(schema).to_json(),
+        }])?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if let Some(Command::Diff { old, new, json }) = &args.command {
+        let old = SchemaSource::from_spec(old, &args, &config).load().await?;
+        let new = SchemaSource::from_spec(new, &args, &config).load().await?;
+        let diff = diff_schemas(&old, &new);
+
+        if *json {
+            println!("{}", diff.to_json());
+        } else if diff.changes.is_empty() {
+            info!("No schema changes");
+        } else {
+            for change in &diff.changes {
+                println!("{change}");
+            }
+        }
+
+        return Ok(if diff.has_breaking_changes() {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        });
+    }
+
+    let jobs = resolve_jobs(&args, &config, registry)?;
+    let source = SchemaSource::new(&args, &config);
+
+    if args.watch {
+        return watch(&args, &source, &jobs).await;
+    }
+
+    let schema = source.load().await?;
+    let files = generate(&schema, &jobs)?;
+
+    if args.check {
+        if files.iter().any(|file| file.path == Path::new(STDOUT)) {
+            return Err(Error::InvalidArgument(
+                "--check compares against files on disk and cannot be used with --output-file -"
+                    .to_string(),
+            ));
+        }
+        return check_output(&files, &stale_files(&jobs, &files)?, &mut std::io::stdout());
+    }
+
+    update_output(&jobs, &files)?;
+
+    for job in jobs
+        .iter()
+        .filter(|job| job.target == "typescript" && job.options.output != Path::new(STDOUT))
+    {
+        let output = &job.options.output;
+        let prefix = if output.is_relative() { "./" } else { "" };
+        info!(
+            "Import with: import {{ TypedHelixDBClient, createTypedClient }} from '{prefix}{}'",
+            output.display()
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn load_config(args: &Args) -> Result<Config> {
+    let path = match &args.config {
+        Some(path) => path.clone(),
+        None => match Config::discover(&std::env::current_dir()?) {
+            Some(path) => path,
+            None => return Ok(Config::default()),
+        },
+    };
+    info!("Using config: {}", path.display());
+    Config::load(&path)
+}
+
+/// Combines the config file's targets with CLI flags. `--target` selects (or
+/// adds) a single target, `--output-file` overrides its output, and
+/// target-specific flags override both `[options]` and per-target settings.
+fn resolve_jobs(args: &Args, config: &Config, registry: &GeneratorRegistry) -> Result<Vec<Job>> {
+    resolve_targets(args, config, registry)?
+        .into_iter()
+        .map(|(target, settings)| {
+            Ok(Job {
+                generator: registry.create(&target.target, &settings)?,
+                options: GeneratorOptions {
+                    output: target.output,
+                },
+                target: target.target,
+            })
+        })
+        .collect()
+}
+
+/// The targets to generate, each with its settings: the `[options]` it
+/// accepts, then its own settings, then CLI flags.
+fn resolve_targets(
+    args: &Args,
+    config: &Config,
+    registry: &GeneratorRegistry,
+) -> Result<Vec<(TargetConfig, serde_json::Value)>> {
+    let mut targets = config.targets.clone();
+
+    if let Some(target) = &args.target {
+        targets.retain(|t| &t.target == target);
+    }
+    if targets.is_empty() {
+        let target = args
+            .target
+            .clone()
+            .unwrap_or_else(|| "typescript".to_string());
+        targets.push(TargetConfig {
+            output: PathBuf::from(default_output(&target)),
+            target,
+            settings: serde_json::Map::new(),
+        });
+    }
+
+    let output = args.output_file.as_ref().map(PathBuf::from);
+    if let Some(output) = output.or_else(|| args.out_dir.clone()) {
+        if targets.len() > 1 {
+            return Err(Error::InvalidArgument(
+                "--output-file and --out-dir are ambiguous when the config declares several targets; select one with --target".to_string(),
+            ));
+        }
+        targets[0].output = output;
+    }
+
+    // Shared options and, with several targets, flags only go to the
+    // targets that accept them. A single target gets everything, so that it
+    // reports what is wrong with a setting.
+    let filter_options = targets.len() > 1 || args.target.is_some();
+    let filter_flags = targets.len() > 1;
+    let flags = cli_settings(args);
+    let (mut applied_options, mut applied_flags) = (HashSet::new(), HashSet::new());
+    let mut resolved = Vec::new();
+    for target in targets {
+        let mut settings = serde_json::Map::new();
+        let layers = [
+            (&config.options, filter_options, &mut applied_options),
+            (&target.settings, false, &mut HashSet::new()),
+            (&flags, filter_flags, &mut applied_flags),
+        ];
+        for (layer, filter, applied) in layers {
+            for (key, value) in layer {
+                if !filter || accepts(registry, &target.target, &settings, key, value) {
+                    settings.insert(key.clone(), value.clone());
+                    applied.insert(key.clone());
+                }
+            }
+        }
+        resolved.push((target, settings.into()));
+    }
+
+    if args.target.is_none()
+        && let Some(key) = config
+            .options
+            .keys()
+            .find(|key| !applied_options.contains(*key))
+    {
+        return Err(Error::InvalidArgument(format!(
+            "`{key}` in [options] is not supported by any configured target"
+        )));
+    }
+    if let Some(key) = flags.keys().find(|key| !applied_flags.contains(*key)) {
+        return Err(Error::InvalidArgument(format!(
+            "--{} is not supported by any configured target; select one with --target",
+            key.replace('_', "-")
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Whether `target` can be created with the setting `key` added to `base`.
+fn accepts(
+    registry: &GeneratorRegistry,
+    target: &str,
+    base: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+    value: &serde_json::Value,
+) -> bool {
+    let mut settings = base.clone();
+    settings.insert(key.to_string(), value.clone());
+    registry.create(target, &settings.into()).is_ok()
+}
+
+fn default_output(target: &str) -> &'static str {
+    match target {
+        "python" => "helix_client.py",
+        "rust" => "helix_client.rs",
+        _ => "helix-client.ts",
+    }
+}
+
+/// Target-specific settings given as CLI flags. Only flags that were set are
+/// included, so backends that do not support them can reject them when
+/// selected explicitly and skip them when the config declares several targets.
+fn cli_settings(args: &Args) -> serde_json::Map<String, serde_json::Value> {
+    let mut settings = serde_json::Map::new();
+    if args.zod {
+        settings.insert("zod".to_string(), true.into());
+    }
+    if args.assertions {
+        settings.insert("assertions".to_string(), true.into());
+    }
+    if args.branded_ids {
+        settings.insert("branded_ids".to_string(), true.into());
+    }
+    if args.out_dir.is_some() {
+        let layout = if args.per_entity {
+            "per-entity"
+        } else {
+            "split"
+        };
+        settings.insert("layout".to_string(), layout.into());
+    }
+    if let Some(client) = &args.client {
+        settings.insert("client".to_string(), client.as_str().into());
+    }
+    if let Some(large_integers) = &args.large_integers {
+        settings.insert("large_integers".to_string(), large_integers.as_str().into());
+    }
+    if let Some(dates) = &args.dates {
+        settings.insert("dates".to_string(), dates.as_str().into());
+    }
+    if let Some(property_naming) = &args.property_naming {
+        settings.insert(
+            "property_naming".to_string(),
+            property_naming.as_str().into(),
+        );
+    }
+    settings
+}
+
+fn generate(schema: &HelixSchema, jobs: &[Job]) -> Result<Vec<OutputFile>> {
+    info!(
+        "Found {} nodes, {} vectors, {} edges, {} queries",
+        schema.nodes.len(),
+        schema.vectors.len(),
+        schema.edges.len(),
+        schema.queries.len()
+    );
+
+    let mut files = Vec::new();
+    for job in jobs {
+        let generated = job.generator.generate(schema, &job.options)?;
+        if job.options.output == Path::new(STDOUT)
+            && generated.iter().any(|file| file.path != Path::new(STDOUT))
+        {
+            return Err(Error::InvalidArgument(format!(
+                "the {} target writes several files and cannot write to stdout",
+                job.target
+            )));
+        }
+        files.extend(generated);
+    }
+    Ok(files)
+}
+
+/// Writes each file, or prints it when its path is `-`.
+fn write_output(files: &[OutputFile]) -> Result<()> {
+    for file in files {
+        if file.path == Path::new(STDOUT) {
+            print!("{}", file.contents);
+            continue;
+        }
+        if let Some(parent) = file.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(Error::file(parent))?;
+        }
+        fs::write(&file.path, &file.contents).map_err(Error::file(&file.path))?;
+        info!("Generated successfully: {}", file.path.display());
+    }
+    Ok(())
+}
+
+/// Writes `files`, then removes the files an earlier run generated in the
+/// same output directories that are no longer generated (e.g. the module of
+/// a deleted node).
+fn update_output(jobs: &[Job], files: &[OutputFile]) -> Result<()> {
+    let stale = stale_files(jobs, files)?;
+    write_output(files)?;
+    for path in stale {
+        fs::remove_file(&path).map_err(Error::file(&path))?;
+        info!("Removed stale file: {}", path.display());
+        // Only succeeds once the directory (e.g. `nodes/`) is empty
+        if let Some(parent) = path.parent() {
+            let _ = fs::remove_dir(parent);
+        }
+    }
+    Ok(())
+}
+
+/// Files under the output directories of `jobs` that carry the generated
+/// header but are not among `files`. Other files are left alone.
+fn stale_files(jobs: &[Job], files: &[OutputFile]) -> Result<Vec<PathBuf>> {
+    let generated: HashSet<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+    let mut stale = Vec::new();
+    for job in jobs.iter().filter(|job| job.options.output.is_dir()) {
+        let mut existing = Vec::new();
+        list_files(&job.options.output, &mut existing)?;
+        existing.sort();
+        stale.extend(existing.into_iter().filter(|path| {
+            !generated.contains(path.as_path())
+                && fs::read_to_string(path).is_ok_and(|contents| {
+                    contents
+                        .lines()
+                        .take(5)
+                        .any(|line| line.ends_with("Generated with helix-ts-gen"))
+                })
+        }));
+    }
+    Ok(stale)
+}
+
+fn list_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).map_err(Error::file(dir))? {
+        let path = entry.map_err(Error::file(dir))?.path();
+        if path.is_dir() {
+            list_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Polls the schema source and rewrites the output only when the schema
+/// changes. Load errors (e.g. a half-edited schema.hx or a restarting
+/// instance) are reported and retried on the next tick; generation errors
+/// (e.g. a name collision) are reported once per schema version.
+async fn watch(args: &Args, source: &SchemaSource, jobs: &[Job]) -> Result<ExitCode> {
+    let mut interval = tokio::time::interval(Duration::from_secs(args.watch_interval.max(1)));
+    let mut last_schema: Option<HelixSchema> = None;
+
+    info!("Watching for schema changes (Ctrl+C to stop)...");
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(ExitCode::SUCCESS),
+        }
+
+        poll(source, jobs, &mut last_schema).await;
+    }
+}
+
+/// One watch tick: regenerates if the schema differs from `last_schema`.
+async fn poll(source: &SchemaSource, jobs: &[Job], last_schema: &mut Option<HelixSchema>) {
+    let schema = match source.load().await {
+        Ok(schema) => schema,
+        Err(e) => {
+            error!("Failed to load schema: {e}");
+            return;
+        }
+    };
+
+    if last_schema.as_ref() == Some(&schema) {
+        return;
+    }
+
+    if let Err(e) = generate(&schema, jobs).and_then(|files| update_output(jobs, &files)) {
+        error!("Failed to generate: {e}");
+    }
+    *last_schema = Some(schema);
+}
+
+/// Prints a unified diff between the files on disk and freshly generated
+/// code, including the removal of `stale` files.
+fn check_output(files: &[OutputFile], stale: &[PathBuf], out: &mut impl Write) -> Result<ExitCode> {
+    let mut out_of_date = false;
+
+    for file in files {
+        let path = file.path.display();
+        let existing = match fs::read_to_string(&file.path) {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(Error::file(&file.path)(e)),
+        };
+
+        if existing == file.contents {
+            info!("{path} is up to date");
+            continue;
+        }
+
+        let diff = TextDiff::from_lines(existing.as_str(), file.contents.as_str());
+        write!(
+            out,
+            "{}",
+            diff.unified_diff()
+                .header(&format!("a/{path}"), &format!("b/{path}"))
+        )?;
+        error!("{path} is out of date; rerun helix-ts-gen without --check to update it");
+        out_of_date = true;
+    }
+
+    for path in stale {
+        let existing = fs::read_to_string(path).map_err(Error::file(path))?;
+        let path = path.display();
+        let diff = TextDiff::from_lines(existing.as_str(), "");
+        write!(
+            out,
+            "{}",
+            diff.unified_diff()
+                .header(&format!("a/{path}"), "/dev/null")
+        )?;
+        error!("{path} is no longer generated; rerun helix-ts-gen without --check to remove it");
+        out_of_date = true;
+    }
+
+    Ok(if out_of_date {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(flags: &[&str]) -> Args {
+        Args::parse_from(std::iter::once("helix-ts-gen").chain(flags.iter().copied()))
+    }
+
+    fn config(source: &str) -> Config {
+        toml::from_str(source).unwrap()
+    }
+
+    /// Each resolved target's name and settings.
+    fn resolve(flags: &[&str], config: &Config) -> Result<Vec<(String, serde_json::Value)>> {
+        let targets = resolve_targets(&args(flags), config, &GeneratorRegistry::default())?;
+        Ok(targets
+            .into_iter()
+            .map(|(target, settings)| (target.target, settings))
+            .collect())
+    }
+
+    const TWO_TARGETS: &str = r#"
+        [options]
+        property_naming = "camel"
+
+        [[targets]]
+        target = "typescript"
+        output = "client.ts"
+        dates = "date"
+
+        [[targets]]
+        target = "rust"
+        output = "client.rs"
+    "#;
+
+    #[test]
+    fn defaults_to_typescript() {
+        let targets = resolve_targets(
+            &args(&[]),
+            &Config::default(),
+            &GeneratorRegistry::default(),
+        )
+        .unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0.target, "typescript");
+        assert_eq!(targets[0].0.output, PathBuf::from("helix-client.ts"));
+        assert_eq!(targets[0].1, serde_json::json!({}));
+    }
+
+    #[test]
+    fn shared_options_only_go_to_targets_that_accept_them() {
+        let targets = resolve(&[], &config(TWO_TARGETS)).unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                (
+                    "typescript".to_string(),
+                    serde_json::json!({ "property_naming": "camel", "dates": "date" })
+                ),
+                ("rust".to_string(), serde_json::json!({})),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_override_target_settings_and_options() {
+        let targets = resolve(
+            &["--dates", "epoch", "--property-naming", "snake", "--zod"],
+            &config(TWO_TARGETS),
+        )
+        .unwrap();
+        assert_eq!(
+            targets[0].1,
+            serde_json::json!({ "property_naming": "snake", "dates": "epoch", "zod": true })
+        );
+        assert_eq!(targets[1].1, serde_json::json!({}));
+    }
+
+    #[test]
+    fn target_flag_selects_one_target() {
+        let targets = resolve(&["--target", "rust"], &config(TWO_TARGETS)).unwrap();
+        assert_eq!(targets, vec![("rust".to_string(), serde_json::json!({}))]);
+
+        let targets = resolve(&["--target", "python"], &config(TWO_TARGETS)).unwrap();
+        assert_eq!(
+            targets,
+            vec![(
+                "python".to_string(),
+                serde_json::json!({ "property_naming": "camel" })
+            )]
+        );
+    }
+
+    #[test]
+    fn unsupported_settings_are_errors() {
+        let error = resolve(
+            &["--zod"],
+            &config(&TWO_TARGETS.replace("typescript", "python")),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "--zod is not supported by any configured target; select one with --target"
+        );
+
+        let error = resolve(&[], &config(&TWO_TARGETS.replace("camel", "kebab"))).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`property_naming` in [options] is not supported by any configured target"
+        );
+    }
+
+    #[test]
+    fn output_is_ambiguous_with_several_targets() {
+        assert!(resolve(&["--output-file", "out.ts"], &config(TWO_TARGETS)).is_err());
+        let targets = resolve_targets(
+            &args(&["--target", "rust", "--output-file", "out.rs"]),
+            &config(TWO_TARGETS),
+            &GeneratorRegistry::default(),
+        )
+        .unwrap();
+        assert_eq!(targets[0].0.output, PathBuf::from("out.rs"));
+    }
+
+    fn jobs(flags: &[&str]) -> Vec<Job> {
+        resolve_jobs(
+            &args(flags),
+            &Config::default(),
+            &GeneratorRegistry::default(),
+        )
+        .unwrap()
+    }
+
+    /// The exit code and diff `--check` prints for `source`.
+    fn check(jobs: &[Job], source: &str) -> (ExitCode, String) {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+                .unwrap();
+        let files = generate(&schema, jobs).unwrap();
+        let mut out = Vec::new();
+        let code = check_output(&files, &stale_files(jobs, &files).unwrap(), &mut out).unwrap();
+        (code, String::from_utf8(out).unwrap())
+    }
+
+    fn write(jobs: &[Job], source: &str) {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+                .unwrap();
+        update_output(jobs, &generate(&schema, jobs).unwrap()).unwrap();
+    }
+
+    const USER: &str = "N::User { name: String }";
+    const USER_AND_TAG: &str = "N::User { name: String }\nN::Tag { label: String }";
+
+    #[test]
+    fn check_diffs_a_stale_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("client.ts");
+        let jobs = jobs(&["--output-file", output.to_str().unwrap()]);
+
+        let (code, diff) = check(&jobs, USER);
+        assert_eq!(code, ExitCode::FAILURE);
+        assert!(diff.contains(&format!("+++ b/{}\n", output.display())));
+
+        write(&jobs, USER);
+        assert_eq!(check(&jobs, USER), (ExitCode::SUCCESS, String::new()));
+
+        let (code, diff) = check(&jobs, USER_AND_TAG);
+        assert_eq!(code, ExitCode::FAILURE);
+        assert!(diff.contains(&format!("--- a/{}\n", output.display())));
+        assert!(diff.contains("\n+export interface Tag extends BaseNode {\n+  label: string;\n"));
+    }
+
+    #[test]
+    fn check_reports_files_no_longer_generated() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("helix");
+        let jobs = jobs(&["--out-dir", out_dir.to_str().unwrap(), "--per-entity"]);
+        write(&jobs, USER_AND_TAG);
+        fs::write(out_dir.join("README.md"), "Not generated\n").unwrap();
+        assert_eq!(
+            check(&jobs, USER_AND_TAG),
+            (ExitCode::SUCCESS, String::new())
+        );
+
+        let tag = out_dir.join("nodes").join("Tag.ts");
+        let (code, diff) = check(&jobs, USER);
+        assert_eq!(code, ExitCode::FAILURE);
+        assert!(diff.contains(&format!("--- a/{}\n+++ /dev/null\n", tag.display())));
+        assert!(!diff.contains("README.md"));
+
+        write(&jobs, USER);
+        assert!(!tag.exists());
+        assert!(out_dir.join("nodes").join("User.ts").exists());
+        assert!(out_dir.join("README.md").exists());
+        assert_eq!(check(&jobs, USER), (ExitCode::SUCCESS, String::new()));
+    }
+
+    #[tokio::test]
+    async fn watch_regenerates_only_when_the_schema_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema_path = dir.path().join("schema.hx");
+        let output = dir.path().join("client.ts");
+        let args = args(&[
+            "--schema-dir",
+            dir.path().to_str().unwrap(),
+            "--output-file",
+            output.to_str().unwrap(),
+        ]);
+        let jobs = resolve_jobs(&args, &Config::default(), &GeneratorRegistry::default()).unwrap();
+        let source = SchemaSource::new(&args, &Config::default());
+        let mut last_schema = None;
+
+        fs::write(&schema_path, USER).unwrap();
+        poll(&source, &jobs, &mut last_schema).await;
+        assert!(
+            fs::read_to_string(&output)
+                .unwrap()
+                .contains("export interface User ")
+        );
+
+        // An unchanged schema leaves the output alone
+        fs::write(&output, "edited").unwrap();
+        poll(&source, &jobs, &mut last_schema).await;
+        assert_eq!(fs::read_to_string(&output).unwrap(), "edited");
+
+        // A half-edited schema is skipped until it parses again
+        fs::write(&schema_path, "N::User { name: ").unwrap();
+        poll(&source, &jobs, &mut last_schema).await;
+        assert_eq!(fs::read_to_string(&output).unwrap(), "edited");
+
+        fs::write(&schema_path, USER_AND_TAG).unwrap();
+        poll(&source, &jobs, &mut last_schema).await;
+        assert!(
+            fs::read_to_string(&output)
+                .unwrap()
+                .contains("export interface Tag ")
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use crate::connection::Auth;
+use crate::error::{Error, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "helix-ts-gen.toml";
+
+/// Project-level settings, read from `helix-ts-gen.toml`.
+///
+/// ```toml
+/// schema_dir = "helixdb-cfg"   # or: endpoint = "http://localhost:6969",
+///                              #  or: snapshot = "schema.json"
+///
+/// [auth]                       # only used with `endpoint`
+/// api_key_header = "x-gateway-key"
+/// headers = { "x-tenant" = "acme" }
+///
+/// [options]                    # for every target that accepts them
+/// property_naming = "camel"
+///
+/// [[targets]]
+/// target = "typescript"
+/// output = "web/src/helix-client.ts"
+/// zod = true
+/// type_mappings = { Point = "[number, number]" }
+///
+/// [[targets]]
+/// target = "python"
+/// output = "api/helix_client.py"
+/// type_mappings = { Point = "tuple[float, float]" }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub endpoint: Option<String>,
+    pub schema_dir: Option<PathBuf>,
+    /// A JSON snapshot written by `helix-ts-gen snapshot`.
+    pub snapshot: Option<PathBuf>,
+    /// Credentials for `endpoint`; environment variables and CLI flags win.
+    #[serde(default)]
+    pub auth: Auth,
+    /// Settings applied to every target that accepts them, below
+    /// target-specific ones.
+    #[serde(default)]
+    pub options: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    pub targets: Vec<TargetConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TargetConfig {
+    /// Backend name, as accepted by `--target`.
+    pub target: String,
+    pub output: PathBuf,
+    /// Backend-specific settings, e.g. `zod = true` for `typescript`.
+    #[serde(flatten)]
+    pub settings: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Config {
+    /// Reads a config file. Relative paths in it are resolved against the
+    /// directory containing the file.
+    pub fn load(path: &Path) -> Result<Config> {
+        let source = fs::read_to_string(path).map_err(Error::file(path))?;
+        let mut config: Config = toml::from_str(&source).map_err(|e| Error::Config {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        if let Some(schema_dir) = &mut config.schema_dir {
+            *schema_dir = base.join(&*schema_dir);
+        }
+        if let Some(snapshot) = &mut config.snapshot {
+            *snapshot = base.join(&*snapshot);
+        }
+        for target in &mut config.targets {
+            target.output = base.join(&target.output);
+        }
+
+        Ok(config)
+    }
+
+    /// Finds `helix-ts-gen.toml` in `start` or its closest ancestor.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_relative_to_the_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(
+            &path,
+            r#"
+            schema_dir = "helixdb-cfg"
+
+            [options]
+            property_naming = "camel"
+
+            [[targets]]
+            target = "typescript"
+            output = "web/client.ts"
+            zod = true
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.schema_dir, Some(dir.path().join("helixdb-cfg")));
+        assert_eq!(config.options["property_naming"], "camel");
+        assert_eq!(config.targets[0].output, dir.path().join("web/client.ts"));
+        assert_eq!(config.targets[0].settings["zod"], true);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "schema = \"helixdb-cfg\"\n").unwrap();
+        assert!(matches!(Config::load(&path), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn discovers_the_closest_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(Config::discover(&nested), None);
+
+        fs::write(dir.path().join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            Config::discover(&nested),
+            Some(dir.path().join(CONFIG_FILE_NAME))
+        );
+        fs::write(nested.join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            Config::discover(&nested),
+            Some(nested.join(CONFIG_FILE_NAME))
+        );
+    }
+}
@@ -1,6 +1,192 @@
+use crate::error::{Error, Result};
+use indexmap::IndexMap;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Header used for `api_key` when no `api_key_header` is set.
+pub const DEFAULT_API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HelixDBConnection {
     pub url: String,
+    #[serde(default)]
+    pub auth: Auth,
+}
+
+impl HelixDBConnection {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            auth: Auth::default(),
+        }
+    }
+
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = auth;
+        self
+    }
+}
+
+/// Credentials sent with every request, for instances behind an API gateway.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Auth {
+    /// Sent as `Authorization: Bearer <token>`.
+    pub bearer_token: Option<String>,
+    pub api_key: Option<String>,
+    /// Header carrying `api_key` (default: `x-api-key`).
+    pub api_key_header: Option<String>,
+    /// Extra headers, sent as-is.
+    pub headers: IndexMap<String, String>,
+}
+
+impl Auth {
+    /// Reads `HELIX_BEARER_TOKEN`, `HELIX_API_KEY` and `HELIX_API_KEY_HEADER`.
+    pub fn from_env() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// [`Self::from_env`] with variables looked up by `var`; empty values
+    /// count as unset.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name| var(name).filter(|value| !value.is_empty());
+        Self {
+            bearer_token: var("HELIX_BEARER_TOKEN"),
+            api_key: var("HELIX_API_KEY"),
+            api_key_header: var("HELIX_API_KEY_HEADER"),
+            headers: IndexMap::new(),
+        }
+    }
+
+    /// Layers `other` on top of `self`: its values win where set, and its
+    /// headers are added to (or replace) ours.
+    pub fn merge(mut self, other: Auth) -> Self {
+        self.bearer_token = other.bearer_token.or(self.bearer_token);
+        self.api_key = other.api_key.or(self.api_key);
+        self.api_key_header = other.api_key_header.or(self.api_key_header);
+        self.headers.extend(other.headers);
+        self
+    }
+
+    pub fn header_map(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(header_name(name)?, header_value(name, value)?);
+        }
+        if let Some(api_key) = &self.api_key {
+            let name = self
+                .api_key_header
+                .as_deref()
+                .unwrap_or(DEFAULT_API_KEY_HEADER);
+            headers.insert(header_name(name)?, header_value(name, api_key)?);
+        }
+        if let Some(token) = &self.bearer_token {
+            headers.insert(
+                AUTHORIZATION,
+                header_value("Authorization", &format!("Bearer {token}"))?,
+            );
+        }
+        Ok(headers)
+    }
+}
+
+fn header_name(name: &str) -> Result<HeaderName> {
+    HeaderName::from_bytes(name.as_bytes()).map_err(|_| Error::InvalidHeader(name.to_string()))
+}
+
+fn header_value(name: &str, value: &str) -> Result<HeaderValue> {
+    let mut value =
+        HeaderValue::from_str(value).map_err(|_| Error::InvalidHeader(name.to_string()))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(bearer_token: Option<&str>, api_key: Option<&str>) -> Auth {
+        Auth {
+            bearer_token: bearer_token.map(String::from),
+            api_key: api_key.map(String::from),
+            ..Auth::default()
+        }
+    }
+
+    #[test]
+    fn reads_variables_and_ignores_empty_ones() {
+        let auth = Auth::from_vars(|name| match name {
+            "HELIX_BEARER_TOKEN" => Some("token".to_string()),
+            "HELIX_API_KEY" => Some(String::new()),
+            "HELIX_API_KEY_HEADER" => Some("x-key".to_string()),
+            _ => None,
+        });
+        assert_eq!(auth.bearer_token.as_deref(), Some("token"));
+        assert_eq!(auth.api_key, None);
+        assert_eq!(auth.api_key_header.as_deref(), Some("x-key"));
+        assert!(auth.headers.is_empty());
+    }
+
+    #[test]
+    fn merged_values_win_where_set() {
+        let mut file = auth(Some("file-token"), Some("file-key"));
+        file.headers
+            .insert("x-tenant".to_string(), "acme".to_string());
+        file.headers
+            .insert("x-region".to_string(), "eu".to_string());
+        let mut flags = auth(None, Some("flag-key"));
+        flags
+            .headers
+            .insert("x-tenant".to_string(), "other".to_string());
+
+        let merged = file.merge(flags);
+        assert_eq!(merged.bearer_token.as_deref(), Some("file-token"));
+        assert_eq!(merged.api_key.as_deref(), Some("flag-key"));
+        assert_eq!(merged.headers["x-tenant"], "other");
+        assert_eq!(merged.headers["x-region"], "eu");
+    }
+
+    #[test]
+    fn header_map_sends_credentials() {
+        let mut credentials = auth(Some("token"), Some("key"));
+        credentials
+            .headers
+            .insert("x-tenant".to_string(), "acme".to_string());
+        let headers = credentials.header_map().unwrap();
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers[AUTHORIZATION], "Bearer token");
+        assert_eq!(headers[DEFAULT_API_KEY_HEADER], "key");
+        assert_eq!(headers["x-tenant"], "acme");
+        assert!(headers[AUTHORIZATION].is_sensitive());
+
+        let credentials = Auth {
+            api_key_header: Some("x-gateway-key".to_string()),
+            ..auth(None, Some("key"))
+        };
+        let headers = credentials.header_map().unwrap();
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["x-gateway-key"], "key");
+    }
+
+    #[test]
+    fn credentials_override_extra_headers() {
+        let mut credentials = auth(Some("token"), None);
+        credentials
+            .headers
+            .insert("Authorization".to_string(), "Basic abc".to_string());
+        let headers = credentials.header_map().unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Bearer token");
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        let mut credentials = Auth::default();
+        credentials
+            .headers
+            .insert("bad header".to_string(), "value".to_string());
+        assert!(matches!(
+            credentials.header_map(),
+            Err(Error::InvalidHeader(name)) if name == "bad header"
+        ));
+    }
 }
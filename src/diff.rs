@@ -0,0 +1,568 @@
+use crate::schema::*;
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+/// Whether a change can break existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Breaking,
+    NonBreaking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementKind {
+    Node,
+    Vector,
+    Edge,
+    Query,
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ElementKind::Node => "node",
+            ElementKind::Vector => "vector",
+            ElementKind::Edge => "edge",
+            ElementKind::Query => "query",
+        })
+    }
+}
+
+/// One difference between two schemas, e.g. a removed property.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaChange {
+    pub severity: Severity,
+    pub kind: ElementKind,
+    /// Name of the node, vector, edge or query.
+    pub name: String,
+    /// Property, parameter or result field, when the change is inside one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Breaking => "BREAKING",
+            Severity::NonBreaking => "ok",
+        };
+        write!(f, "{label:<9}{} {}", self.kind, self.name)?;
+        if let Some(member) = &self.member {
+            write!(f, ".{member}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.severity == Severity::Breaking)
+    }
+
+    /// `{ "breaking": bool, "changes": [...] }`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&serde_json::json!({
+            "breaking": self.has_breaking_changes(),
+            "changes": self.changes,
+        }))
+        .expect("diff serialization cannot fail")
+    }
+}
+
+/// An entity's property types and defaults.
+type Properties<'a> = (
+    &'a IndexMap<String, FieldType>,
+    &'a IndexMap<String, DefaultValue>,
+);
+
+/// Compares two versions of a schema from the point of view of a generated
+/// client: entities are read (so removing or changing a property breaks) and
+/// created (so a property that must now be passed breaks), query parameters
+/// are written (so a new required parameter breaks), and query results are
+/// read.
+pub fn diff_schemas(old: &HelixSchema, new: &HelixSchema) -> SchemaDiff {
+    let mut differ = Differ::default();
+
+    differ.entities(ElementKind::Node, &old.nodes, &new.nodes, |node| {
+        (&node.properties, &node.defaults)
+    });
+    differ.entities(ElementKind::Vector, &old.vectors, &new.vectors, |vector| {
+        (&vector.properties, &vector.defaults)
+    });
+    differ.entities(ElementKind::Edge, &old.edges, &new.edges, |edge| {
+        (&edge.properties, &edge.defaults)
+    });
+    for (name, old_edge) in &old.edges {
+        if let Some(new_edge) = new.edges.get(name) {
+            for (end, old_node, new_node) in [
+                ("from", &old_edge.from_node, &new_edge.from_node),
+                ("to", &old_edge.to_node, &new_edge.to_node),
+            ] {
+                if old_node != new_node {
+                    differ.push(
+                        Severity::Breaking,
+                        ElementKind::Edge,
+                        name,
+                        Some(end),
+                        format!("endpoint changed from {old_node} to {new_node}"),
+                    );
+                }
+            }
+        }
+    }
+    differ.queries(&old.queries, &new.queries);
+
+    SchemaDiff {
+        changes: differ.changes,
+    }
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<SchemaChange>,
+}
+
+impl Differ {
+    fn push(
+        &mut self,
+        severity: Severity,
+        kind: ElementKind,
+        name: &str,
+        member: Option<&str>,
+        message: String,
+    ) {
+        self.changes.push(SchemaChange {
+            severity,
+            kind,
+            name: name.to_string(),
+            member: member.map(str::to_string),
+            message,
+        });
+    }
+
+    /// Reports added and removed elements, and returns the pairs present in
+    /// both schemas.
+    fn elements<'a, T>(
+        &mut self,
+        kind: ElementKind,
+        old: &'a IndexMap<String, T>,
+        new: &'a IndexMap<String, T>,
+    ) -> Vec<(&'a String, &'a T, &'a T)> {
+        for name in old.keys().filter(|name| !new.contains_key(*name)) {
+            self.push(Severity::Breaking, kind, name, None, "removed".to_string());
+        }
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            self.push(Severity::NonBreaking, kind, name, None, "added".to_string());
+        }
+        old.iter()
+            .filter_map(|(name, old)| new.get(name).map(|new| (name, old, new)))
+            .collect()
+    }
+
+    fn entities<T>(
+        &mut self,
+        kind: ElementKind,
+        old: &IndexMap<String, T>,
+        new: &IndexMap<String, T>,
+        properties: impl Fn(&T) -> Properties<'_>,
+    ) {
+        for (name, old, new) in self.elements(kind, old, new) {
+            let ((old, old_defaults), (new, new_defaults)) = (properties(old), properties(new));
+            for (property, old_type) in old {
+                match new.get(property) {
+                    None => self.push(
+                        Severity::Breaking,
+                        kind,
+                        name,
+                        Some(property),
+                        "property removed".to_string(),
+                    ),
+                    Some(new_type) if !same_type(old_type, new_type) => {
+                        // Readers cope with a value that can no longer be
+                        // missing, but creators must now pass it.
+                        let severity = match old_type {
+                            FieldType::Optional(inner)
+                                if same_type(inner, new_type)
+                                    && new_defaults.contains_key(property) =>
+                            {
+                                Severity::NonBreaking
+                            }
+                            _ => Severity::Breaking,
+                        };
+                        self.push(
+                            severity,
+                            kind,
+                            name,
+                            Some(property),
+                            format!("type changed from {old_type} to {new_type}"),
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (property, new_type) in new.iter().filter(|(p, _)| !old.contains_key(*p)) {
+                let omittable = matches!(new_type, FieldType::Optional(_))
+                    || new_defaults.contains_key(property);
+                self.push(
+                    if omittable {
+                        Severity::NonBreaking
+                    } else {
+                        Severity::Breaking
+                    },
+                    kind,
+                    name,
+                    Some(property),
+                    format!("property added ({new_type})"),
+                );
+            }
+            for property in old.keys().filter(|p| new.contains_key(*p)) {
+                // Creators that relied on a default must now pass the value,
+                // unless the property is optional.
+                let (severity, message) =
+                    match (old_defaults.get(property), new_defaults.get(property)) {
+                        (Some(old), None) => (
+                            match new[property] {
+                                FieldType::Optional(_) => Severity::NonBreaking,
+                                _ => Severity::Breaking,
+                            },
+                            format!("default {old} removed"),
+                        ),
+                        (None, Some(new)) => {
+                            (Severity::NonBreaking, format!("default {new} added"))
+                        }
+                        (Some(old), Some(new)) if old != new => (
+                            Severity::NonBreaking,
+                            format!("default changed from {old} to {new}"),
+                        ),
+                        _ => continue,
+                    };
+                self.push(severity, kind, name, Some(property), message);
+            }
+        }
+    }
+
+    fn queries(
+        &mut self,
+        old: &IndexMap<String, QueryDefinition>,
+        new: &IndexMap<String, QueryDefinition>,
+    ) {
+        let kind = ElementKind::Query;
+        for (name, old, new) in self.elements(kind, old, new) {
+            for (param, old_type) in &old.parameters {
+                match new.parameters.get(param) {
+                    None => self.push(
+                        Severity::Breaking,
+                        kind,
+                        name,
+                        Some(param),
+                        "parameter removed".to_string(),
+                    ),
+                    Some(new_type) if !same_type(old_type, new_type) => {
+                        // Callers may keep sending a value that became optional.
+                        let severity = match new_type {
+                            FieldType::Optional(inner) if same_type(old_type, inner) => {
+                                Severity::NonBreaking
+                            }
+                            _ => Severity::Breaking,
+                        };
+                        self.push(
+                            severity,
+                            kind,
+                            name,
+                            Some(param),
+                            format!("parameter type changed from {old_type} to {new_type}"),
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (param, new_type) in new
+                .parameters
+                .iter()
+                .filter(|(p, _)| !old.parameters.contains_key(*p))
+            {
+                let severity = match new_type {
+                    FieldType::Optional(_) => Severity::NonBreaking,
+                    _ => Severity::Breaking,
+                };
+                self.push(
+                    severity,
+                    kind,
+                    name,
+                    Some(param),
+                    format!("parameter added ({new_type})"),
+                );
+            }
+
+            for old_field in &old.returns {
+                match new.returns.iter().find(|f| f.name == old_field.name) {
+                    None => self.push(
+                        Severity::Breaking,
+                        kind,
+                        name,
+                        Some(&old_field.name),
+                        "result field removed".to_string(),
+                    ),
+                    // An unknown type (e.g. from introspection without query
+                    // bodies) says nothing about what changed.
+                    Some(new_field)
+                        if old_field.return_type != ReturnType::Unknown
+                            && new_field.return_type != ReturnType::Unknown
+                            && old_field.return_type.to_string()
+                                != new_field.return_type.to_string() =>
+                    {
+                        self.push(
+                            Severity::Breaking,
+                            kind,
+                            name,
+                            Some(&old_field.name),
+                            format!(
+                                "result type changed from {} to {}",
+                                old_field.return_type, new_field.return_type
+                            ),
+                        );
+                    }
+                    Some(_) => {}
+                }
+            }
+            for new_field in new
+                .returns
+                .iter()
+                .filter(|f| !old.returns.iter().any(|o| o.name == f.name))
+            {
+                self.push(
+                    Severity::NonBreaking,
+                    kind,
+                    name,
+                    Some(&new_field.name),
+                    format!("result field added ({})", new_field.return_type),
+                );
+            }
+        }
+    }
+}
+
+/// Types are compared as they appear on the wire, so an `ID` parameter that
+/// inference narrowed to a particular entity's ID is not a change.
+fn same_type(old: &FieldType, new: &FieldType) -> bool {
+    old.to_string() == new.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    fn schema(source: &str) -> HelixSchema {
+        HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+            .unwrap()
+    }
+
+    /// `(severity, member, message)` of each change between two sources.
+    fn changes(old: &str, new: &str) -> Vec<(Severity, Option<String>, String)> {
+        diff_schemas(&schema(old), &schema(new))
+            .changes
+            .into_iter()
+            .map(|change| (change.severity, change.member, change.message))
+            .collect()
+    }
+
+    fn severity(old: &str, new: &str) -> Severity {
+        let changes = changes(old, new);
+        assert_eq!(changes.len(), 1, "{changes:?}");
+        changes[0].0
+    }
+
+    #[test]
+    fn properties_required_on_creation_are_breaking() {
+        let old = "N::User { Name: String }";
+        assert_eq!(
+            severity(old, "N::User { Name: String, Email: String }"),
+            Severity::Breaking
+        );
+        assert_eq!(
+            severity(old, "N::User { Name: String, Email: String? }"),
+            Severity::NonBreaking
+        );
+        assert_eq!(
+            severity(old, "N::User { Name: String, Age: U8 DEFAULT 0 }"),
+            Severity::NonBreaking
+        );
+    }
+
+    #[test]
+    fn optional_property_becoming_required_needs_a_default() {
+        let old = "N::User { Nick: String? }";
+        assert_eq!(
+            changes(old, "N::User { Nick: String }"),
+            vec![(
+                Severity::Breaking,
+                Some("Nick".to_string()),
+                "type changed from String? to String".to_string()
+            )]
+        );
+        let with_default = changes(old, "N::User { Nick: String DEFAULT \"anon\" }");
+        assert!(
+            with_default
+                .iter()
+                .all(|(severity, _, _)| *severity == Severity::NonBreaking),
+            "{with_default:?}"
+        );
+    }
+
+    #[test]
+    fn removing_a_default_breaks_unless_optional() {
+        assert_eq!(
+            severity("N::User { Age: U8 DEFAULT 0 }", "N::User { Age: U8 }"),
+            Severity::Breaking
+        );
+        assert_eq!(
+            severity("N::User { Age: U8? DEFAULT 0 }", "N::User { Age: U8? }"),
+            Severity::NonBreaking
+        );
+        assert_eq!(
+            severity(
+                "N::User { Age: U8 DEFAULT 0 }",
+                "N::User { Age: U8 DEFAULT 1 }"
+            ),
+            Severity::NonBreaking
+        );
+    }
+
+    const USER: &str = "N::User { Name: String, Age: U8 }\n";
+
+    #[test]
+    fn identical_schemas_have_no_changes() {
+        let diff = diff_schemas(&schema(USER), &schema(USER));
+        assert!(diff.changes.is_empty());
+        assert!(!diff.has_breaking_changes());
+    }
+
+    #[test]
+    fn removing_is_breaking_and_adding_is_not() {
+        assert_eq!(
+            changes(USER, "N::Post { Title: String }"),
+            vec![
+                (Severity::Breaking, None, "removed".to_string()),
+                (Severity::NonBreaking, None, "added".to_string()),
+            ]
+        );
+        assert_eq!(
+            changes(USER, "N::User { Name: String }"),
+            vec![(
+                Severity::Breaking,
+                Some("Age".to_string()),
+                "property removed".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn property_type_changes_are_breaking() {
+        assert_eq!(
+            severity(USER, "N::User { Name: String, Age: U16 }"),
+            Severity::Breaking
+        );
+        // Readers must now handle a missing value
+        assert_eq!(
+            severity(USER, "N::User { Name: String, Age: U8? }"),
+            Severity::Breaking
+        );
+    }
+
+    #[test]
+    fn edge_endpoint_changes_are_breaking() {
+        let nodes = "N::User { Name: String }\nN::Team { Name: String }\n";
+        let old = format!("{nodes}E::Member {{ From: User, To: Team, Properties: {{}} }}");
+        let new = format!("{nodes}E::Member {{ From: User, To: User, Properties: {{}} }}");
+        assert_eq!(
+            changes(&old, &new),
+            vec![(
+                Severity::Breaking,
+                Some("to".to_string()),
+                "endpoint changed from Team to User".to_string()
+            )]
+        );
+    }
+
+    fn query(signature: &str, returns: &str) -> String {
+        format!("{USER}QUERY {signature} =>\n    user <- N<User>(id)\n    RETURN {returns}\n")
+    }
+
+    #[test]
+    fn parameters_are_written() {
+        let old = query("Get(id: ID)", "user");
+        assert_eq!(
+            severity(&old, &query("Get(id: ID, limit: U32)", "user")),
+            Severity::Breaking
+        );
+        assert_eq!(
+            severity(&old, &query("Get(id: ID, limit: U32?)", "user")),
+            Severity::NonBreaking
+        );
+        assert_eq!(
+            severity(&old, &query("Get(id: ID?)", "user")),
+            Severity::NonBreaking
+        );
+        assert_eq!(
+            severity(&query("Get(id: ID?)", "user"), &old),
+            Severity::Breaking
+        );
+    }
+
+    #[test]
+    fn results_are_read() {
+        let old = query("Get(id: ID)", "user");
+        assert_eq!(
+            severity(&old, &query("Get(id: ID)", "user, \"ok\"")),
+            Severity::NonBreaking
+        );
+        assert_eq!(
+            severity(&query("Get(id: ID)", "user, \"ok\""), &old),
+            Severity::Breaking
+        );
+        assert_eq!(
+            changes(&old, &query("Get(id: ID)", "user::Name")),
+            vec![(
+                Severity::Breaking,
+                Some("user".to_string()),
+                "result type changed from User to String".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_result_types_are_not_compared() {
+        let old = schema(&query("Get(id: ID)", "user"));
+        let mut new = old.clone();
+        new.queries["Get"].returns[0].return_type = ReturnType::Unknown;
+        assert!(diff_schemas(&old, &new).changes.is_empty());
+    }
+
+    #[test]
+    fn output_formats() {
+        let diff = diff_schemas(&schema(USER), &schema("N::User { Name: String }"));
+        assert!(diff.has_breaking_changes());
+        assert_eq!(
+            diff.changes[0].to_string(),
+            "BREAKING node User.Age: property removed"
+        );
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json()).unwrap();
+        assert_eq!(json["breaking"], true);
+        assert_eq!(json["changes"][0]["severity"], "breaking");
+        assert_eq!(json["changes"][0]["kind"], "node");
+        assert_eq!(json["changes"][0]["member"], "Age");
+    }
+}
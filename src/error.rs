@@ -1,11 +1,193 @@
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Reqwest Error")]
+    #[error("could not connect to HelixDB at {url}: {source}")]
+    Connection {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("{url} responded with {status}{}", body_excerpt(.body))]
+    Status {
+        url: String,
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("malformed introspection response at `{path}`: {message}")]
+    Introspection { path: String, message: String },
+    #[error("{}:{line}:{column}: {message}{}", .path.display(), snippet(.source_line, *.column))]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+        /// The offending line of source, for pointing at `column`.
+        source_line: String,
+    },
+    #[error("unknown type `{name}` in {context}")]
+    UnknownType { name: String, context: String },
+    #[error("invalid type `{type_name}`: {message}")]
+    InvalidType { type_name: String, message: String },
+    #[error("`{name}` in {context} is not a valid identifier")]
+    InvalidIdentifier { name: String, context: String },
+    #[error("{first} and {second} both generate `{generated}` in {scope}")]
+    NameCollision {
+        first: String,
+        second: String,
+        generated: String,
+        scope: String,
+    },
+    #[error("no .hx files found in {}", .0.display())]
+    NoSchemaFiles(PathBuf),
+    #[error("invalid config file {}: {message}", .path.display())]
+    Config { path: PathBuf, message: String },
+    #[error("invalid schema snapshot: {0}")]
+    Snapshot(String),
+    #[error("unknown target `{target}` (available: {})", .available.join(", "))]
+    UnknownTarget {
+        target: String,
+        available: Vec<String>,
+    },
+    #[error("invalid generator settings: {0}")]
+    InvalidSettings(String),
+    #[error("invalid value for header `{0}`")]
+    InvalidHeader(String),
+    #[error("{0}")]
+    InvalidArgument(String),
+    #[error("{}: {source}", .path.display())]
+    File {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("HTTP error: {0}")]
     Reqwest(#[from] reqwest::Error),
-    #[error("I/O Error")]
+    #[error("I/O error: {0}")]
     IO(#[from] std::io::Error),
 }
 
+impl Error {
+    /// Wraps an I/O error with the path it concerns.
+    pub fn file(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Error {
+        let path = path.into();
+        move |source| Error::File { path, source }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
+
+fn body_excerpt(body: &str) -> String {
+    const MAX_LEN: usize = 500;
+    let body = body.trim();
+    match body.char_indices().nth(MAX_LEN) {
+        _ if body.is_empty() => String::new(),
+        Some((end, _)) => format!(": {}...", &body[..end]),
+        None => format!(": {body}"),
+    }
+}
+
+/// The source line with a caret under `column`, rustc-style.
+fn snippet(source_line: &str, column: usize) -> String {
+    if source_line.is_empty() {
+        return String::new();
+    }
+    let indent: String = source_line
+        .chars()
+        .take(column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    format!("\n  |\n  | {source_line}\n  | {indent}^")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::GeneratorRegistry;
+
+    /// The message of the error creating `target` with `settings`.
+    fn settings_error(target: &str, settings: serde_json::Value) -> String {
+        match GeneratorRegistry::default().create(target, &settings) {
+            Ok(_) => panic!("settings were accepted"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn settings_errors_name_the_field() {
+        assert_eq!(
+            settings_error("rust", serde_json::json!({ "dates": "epoch" })),
+            "invalid generator settings: unknown field `dates`, expected `type_mappings`"
+        );
+        assert_eq!(
+            settings_error("typescript", serde_json::json!({ "dates": "unix" })),
+            "invalid generator settings: unknown variant `unix`, expected one of `iso-string`, `date`, `epoch`"
+        );
+        assert_eq!(
+            settings_error("swift", serde_json::Value::Null),
+            "unknown target `swift` (available: typescript, python, rust)"
+        );
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_column() {
+        let error = Error::Parse {
+            path: PathBuf::from("schema.hx"),
+            line: 3,
+            column: 12,
+            message: "expected `}`".to_string(),
+            source_line: "N::User { name String }".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "schema.hx:3:12: expected `}`\n  |\n  | N::User { name String }\n  |            ^"
+        );
+    }
+
+    #[test]
+    fn status_errors_include_a_body_excerpt() {
+        let status = |body: &str| Error::Status {
+            url: "http://localhost:6969/introspect".to_string(),
+            status: reqwest::StatusCode::UNAUTHORIZED,
+            body: body.to_string(),
+        };
+        assert_eq!(
+            status("").to_string(),
+            "http://localhost:6969/introspect responded with 401 Unauthorized"
+        );
+        assert_eq!(
+            status(" missing api key\n").to_string(),
+            "http://localhost:6969/introspect responded with 401 Unauthorized: missing api key"
+        );
+        let long = status(&"x".repeat(600)).to_string();
+        assert!(long.ends_with(&format!(": {}...", "x".repeat(500))));
+    }
+
+    #[test]
+    fn schema_errors_name_what_is_wrong() {
+        let collision = Error::NameCollision {
+            first: "query `getUser`".to_string(),
+            second: "query `get_user`".to_string(),
+            generated: "GetUserParams".to_string(),
+            scope: "TypeScript declarations".to_string(),
+        };
+        assert_eq!(
+            collision.to_string(),
+            "query `getUser` and query `get_user` both generate `GetUserParams` in TypeScript declarations"
+        );
+        let unknown = Error::UnknownType {
+            name: "Ghost".to_string(),
+            context: "query `getGhost`".to_string(),
+        };
+        assert_eq!(
+            unknown.to_string(),
+            "unknown type `Ghost` in query `getGhost`"
+        );
+        let file = Error::file("schema.hx")(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "not found",
+        ));
+        assert_eq!(file.to_string(), "schema.hx: not found");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::{Error, Result};
+use crate::py_generator::{PythonGenerator, PythonOptions};
+use crate::rs_generator::{RustGenerator, RustOptions};
+use crate::schema::HelixSchema;
+use crate::ts_generator::TypeScriptGenerator;
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// A generated file, ready to be written to disk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Options shared by every backend.
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Output file (or directory, for multi-file backends).
+    pub output: PathBuf,
+}
+
+/// A code generation backend. Implement this in your own crate and add it to
+/// a [`GeneratorRegistry`] to make it selectable by target name.
+pub trait Generator {
+    fn generate(&self, schema: &HelixSchema, options: &GeneratorOptions)
+    -> Result<Vec<OutputFile>>;
+}
+
+/// Builds a backend from its target-specific settings (a JSON object, as
+/// supplied by CLI flags or a config file).
+pub type GeneratorFactory = fn(&serde_json::Value) -> Result<Box<dyn Generator>>;
+
+/// Maps target names (`--target typescript`) to backend factories.
+pub struct GeneratorRegistry {
+    factories: IndexMap<String, GeneratorFactory>,
+}
+
+impl GeneratorRegistry {
+    pub fn empty() -> Self {
+        Self {
+            factories: IndexMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, target: impl Into<String>, factory: GeneratorFactory) {
+        self.factories.insert(target.into(), factory);
+    }
+
+    pub fn targets(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    pub fn create(&self, target: &str, settings: &serde_json::Value) -> Result<Box<dyn Generator>> {
+        let factory = self
+            .factories
+            .get(target)
+            .ok_or_else(|| Error::UnknownTarget {
+                target: target.to_string(),
+                available: self.targets().map(str::to_string).collect(),
+            })?;
+        factory(settings)
+    }
+}
+
+impl Default for GeneratorRegistry {
+    /// A registry containing the built-in backends.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("typescript", |settings| {
+            Ok(Box::new(TypeScriptGenerator::new(parse_settings(
+                settings,
+            )?)))
+        });
+        registry.register("python", |settings| {
+            Ok(Box::new(PythonGenerator::new(parse_settings::<
+                PythonOptions,
+            >(settings)?)))
+        });
+        registry.register("rust", |settings| {
+            Ok(Box::new(RustGenerator::new(parse_settings::<RustOptions>(
+                settings,
+            )?)))
+        });
+        registry
+    }
+}
+
+/// Deserializes target-specific settings, treating `null` as all defaults.
+pub fn parse_settings<T: DeserializeOwned + Default>(settings: &serde_json::Value) -> Result<T> {
+    if settings.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(settings.clone()).map_err(|e| Error::InvalidSettings(e.to_string()))
+}
@@ -0,0 +1,424 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Splits a name into words at separators (`_`, `-`, ...), at case changes
+/// (`userName`), and before the last capital of an acronym followed by a
+/// word (`HTTPServer` -> `HTTP`, `Server`). Digits stay with the word they
+/// follow (`top10Users` -> `top10`, `Users`).
+pub fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if !current.is_empty() && c.is_uppercase() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Leading underscores are kept, so `_id` does not become `id`.
+fn convert(name: &str, separator: &str, word: impl Fn(usize, &str) -> String) -> String {
+    let prefix_len = name.len() - name.trim_start_matches('_').len();
+    let converted: Vec<String> = words(name)
+        .iter()
+        .enumerate()
+        .map(|(i, w)| word(i, w))
+        .collect();
+    format!("{}{}", &name[..prefix_len], converted.join(separator))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+    }
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    convert(name, "_", |_, word| word.to_lowercase())
+}
+
+pub fn to_screaming_snake_case(name: &str) -> String {
+    convert(name, "_", |_, word| word.to_uppercase())
+}
+
+/// Acronyms keep their case after the first word: `userID` -> `UserID`.
+pub fn to_pascal_case(name: &str) -> String {
+    convert(name, "", |_, word| capitalize(word))
+}
+
+/// `HTTPStatus` -> `httpStatus`, `user_ID` -> `userID`.
+pub fn to_camel_case(name: &str) -> String {
+    convert(name, "", |i, word| match i {
+        0 => word.to_lowercase(),
+        _ => capitalize(word),
+    })
+}
+
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// How property and parameter names from the schema are spelled in
+/// generated code. Requests and responses keep the schema's spelling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingStrategy {
+    /// As declared in the schema.
+    #[default]
+    Preserve,
+    Snake,
+    Camel,
+    Pascal,
+}
+
+impl NamingStrategy {
+    /// Names that are not plain identifiers (e.g. string keys in `RETURN`)
+    /// are left alone.
+    pub fn apply(self, name: &str) -> String {
+        if !is_identifier(name) {
+            return name.to_string();
+        }
+        match self {
+            NamingStrategy::Preserve => name.to_string(),
+            NamingStrategy::Snake => to_snake_case(name),
+            NamingStrategy::Camel => to_camel_case(name),
+            NamingStrategy::Pascal => to_pascal_case(name),
+        }
+    }
+}
+
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "any",
+    "as",
+    "bigint",
+    "boolean",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "declare",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "never",
+    "new",
+    "null",
+    "number",
+    "object",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "string",
+    "super",
+    "switch",
+    "symbol",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "undefined",
+    "unknown",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Globals and generated declarations that generated TypeScript refers to.
+const TYPESCRIPT_RESERVED: &[&str] = &[
+    "AbortSignal",
+    "AnyEdge",
+    "AnyNode",
+    "AnyVector",
+    "Array",
+    "Awaited",
+    "BaseEdge",
+    "BaseNode",
+    "BaseVector",
+    "BigInt",
+    "Boolean",
+    "Brand",
+    "Check",
+    "Convert",
+    "Date",
+    "Error",
+    "HelixAuthConfig",
+    "HelixDB",
+    "HelixDBClient",
+    "HelixDBError",
+    "HelixDBInput",
+    "HelixDBResponse",
+    "HelixID",
+    "JSON",
+    "Number",
+    "Object",
+    "Omit",
+    "Optional",
+    "Parameters",
+    "Partial",
+    "Promise",
+    "QueryEndpoints",
+    "Record",
+    "RequestOptions",
+    "ReturnType",
+    "String",
+    "TypeError",
+    "TypedClientOptions",
+    "TypedHelixDBClient",
+    "Vector",
+    "z",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Names imported or declared by generated Python.
+const PYTHON_RESERVED: &[&str] = &[
+    "Any",
+    "BaseEdge",
+    "BaseModel",
+    "BaseNode",
+    "BaseVector",
+    "Client",
+    "ConfigDict",
+    "Dict",
+    "Field",
+    "HelixID",
+    "HelixModel",
+    "List",
+    "Optional",
+    "QueryEndpoints",
+    "TypedHelixDBClient",
+    "Vector",
+    "bool",
+    "datetime",
+    "dict",
+    "float",
+    "int",
+    "list",
+    "str",
+];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Types generated Rust refers to unqualified.
+const RUST_RESERVED: &[&str] = &[
+    "DeserializeOwned",
+    "Deserialize",
+    "Future",
+    "HelixDBClient",
+    "HelixID",
+    "Option",
+    "Result",
+    "Serialize",
+    "String",
+    "Timestamp",
+    "TypedHelixDBClient",
+    "Vec",
+];
+
+/// A target language, for its reserved words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    TypeScript,
+    Python,
+    Rust,
+}
+
+impl Language {
+    pub fn is_keyword(self, name: &str) -> bool {
+        match self {
+            Language::TypeScript => TYPESCRIPT_KEYWORDS.contains(&name),
+            Language::Python => PYTHON_KEYWORDS.contains(&name),
+            Language::Rust => RUST_KEYWORDS.contains(&name),
+        }
+    }
+
+    /// Keywords, plus names the generated code already uses for something
+    /// else (built-in types, helpers).
+    pub fn is_reserved(self, name: &str) -> bool {
+        self.is_keyword(name)
+            || match self {
+                Language::TypeScript => TYPESCRIPT_RESERVED.contains(&name),
+                Language::Python => PYTHON_RESERVED.contains(&name),
+                Language::Rust => RUST_RESERVED.contains(&name),
+            }
+    }
+
+    /// The name under which an entity or query type is declared: `name`,
+    /// or `name_` if reserved (a node `Date` becomes `Date_`).
+    pub fn type_name(self, name: &str) -> String {
+        if self.is_reserved(name) {
+            format!("{name}_")
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Generated names in one scope, for rejecting two schema names that
+/// would generate the same one.
+pub struct Namespace {
+    scope: String,
+    names: HashMap<String, String>,
+}
+
+impl Namespace {
+    pub fn new(scope: impl Into<String>) -> Self {
+        Self {
+            scope: scope.into(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Records that `source` generates `generated`.
+    pub fn claim(&mut self, source: &str, generated: impl Into<String>) -> Result<()> {
+        let generated = generated.into();
+        match self.names.get(&generated) {
+            Some(first) if first != source => Err(Error::NameCollision {
+                first: first.clone(),
+                second: source.to_string(),
+                generated,
+                scope: self.scope.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.names.insert(generated, source.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_split_on_case_acronyms_and_separators() {
+        assert_eq!(words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(words("top10Users"), ["top10", "Users"]);
+        assert_eq!(words("userName"), ["user", "Name"]);
+        assert_eq!(words("user_name-id"), ["user", "name", "id"]);
+        assert_eq!(words("getHTTPStatus"), ["get", "HTTP", "Status"]);
+        assert_eq!(words("userID"), ["user", "ID"]);
+        assert!(words("__").is_empty());
+    }
+
+    #[test]
+    fn case_conversions() {
+        assert_eq!(to_snake_case("getHTTPStatus"), "get_http_status");
+        assert_eq!(to_snake_case("Top10Users"), "top10_users");
+        assert_eq!(to_snake_case("_id"), "_id");
+        assert_eq!(to_screaming_snake_case("getUser"), "GET_USER");
+        assert_eq!(to_pascal_case("userID"), "UserID");
+        assert_eq!(to_pascal_case("get_user"), "GetUser");
+        assert_eq!(to_camel_case("HTTPStatus"), "httpStatus");
+        assert_eq!(to_camel_case("user_ID"), "userID");
+        assert_eq!(to_camel_case("CreatedAt"), "createdAt");
+    }
+
+    #[test]
+    fn naming_strategy_leaves_non_identifiers_alone() {
+        assert_eq!(NamingStrategy::Camel.apply("created_at"), "createdAt");
+        assert_eq!(NamingStrategy::Snake.apply("CreatedAt"), "created_at");
+        assert_eq!(NamingStrategy::Pascal.apply("name"), "Name");
+        assert_eq!(NamingStrategy::Preserve.apply("CreatedAt"), "CreatedAt");
+        assert_eq!(NamingStrategy::Snake.apply("some key"), "some key");
+        assert_eq!(NamingStrategy::Camel.apply("1st"), "1st");
+    }
+
+    #[test]
+    fn reserved_names_get_a_trailing_underscore() {
+        assert_eq!(Language::TypeScript.type_name("Date"), "Date_");
+        assert_eq!(Language::TypeScript.type_name("User"), "User");
+        assert_eq!(Language::Python.type_name("str"), "str_");
+        assert_eq!(Language::Rust.type_name("Option"), "Option_");
+        assert!(Language::TypeScript.is_keyword("default"));
+        assert!(!Language::TypeScript.is_keyword("Date"));
+        assert!(Language::Rust.is_keyword("type"));
+    }
+
+    #[test]
+    fn namespace_rejects_two_sources_for_one_name() {
+        let mut namespace = Namespace::new("User");
+        namespace
+            .claim("property `CreatedAt`", "createdAt")
+            .unwrap();
+        namespace
+            .claim("property `CreatedAt`", "createdAt")
+            .unwrap();
+        match namespace.claim("built-in field `created_at`", "createdAt") {
+            Err(Error::NameCollision {
+                first,
+                second,
+                generated,
+                scope,
+            }) => {
+                assert_eq!(first, "property `CreatedAt`");
+                assert_eq!(second, "built-in field `created_at`");
+                assert_eq!(generated, "createdAt");
+                assert_eq!(scope, "User");
+            }
+            other => panic!("expected a collision, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,680 @@
+use crate::error::{Error, Result};
+use crate::parser::{Spanned, Token, split_top_level};
+use crate::schema::*;
+use indexmap::IndexMap;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// What type-checking a query body reveals beyond its declared signature.
+pub(crate) struct InferredQuery {
+    pub returns: Vec<ReturnField>,
+    /// `ID` parameters used to look up a specific entity, e.g. `user_id` in
+    /// `N<User>(user_id)`, mapped to that entity's name.
+    pub id_targets: IndexMap<String, String>,
+}
+
+impl InferredQuery {
+    pub fn apply_to(self, query: &mut QueryDefinition) {
+        query.returns = self.returns;
+        for (param, entity) in self.id_targets {
+            if let Some(field_type) = query.parameters.get_mut(&param) {
+                *field_type = FieldType::EntityID(entity);
+            }
+        }
+    }
+}
+
+/// Infers the shape of a query's RETURN clause by type-checking the
+/// traversals in its body against the schema. Fails if the body names a
+/// node, vector or edge the schema does not declare.
+pub(crate) fn infer_query(
+    schema: &HelixSchema,
+    query: &QueryDefinition,
+    body: &[Spanned],
+) -> Result<InferredQuery> {
+    let mut checker = TypeChecker {
+        schema,
+        parameters: &query.parameters,
+        id_targets: RefCell::new(IndexMap::new()),
+        unknown_type: RefCell::new(None),
+        variables: query
+            .parameters
+            .iter()
+            .map(|(name, field_type)| (name.clone(), ReturnType::Scalar(field_type.clone())))
+            .collect(),
+    };
+
+    let mut returns = Vec::new();
+    for statement in split_statements(body) {
+        match statement {
+            Statement::Assign(name, expr) => {
+                let return_type = checker.check(expr);
+                checker.variables.insert(name, return_type);
+            }
+            Statement::Return(items) => {
+                returns = split_top_level(items)
+                    .into_iter()
+                    .map(|item| ReturnField {
+                        name: return_item_name(item),
+                        return_type: checker.check(item),
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    if let Some(name) = checker.unknown_type.into_inner() {
+        return Err(Error::UnknownType {
+            name,
+            context: format!("query `{}`", query.name),
+        });
+    }
+    Ok(InferredQuery {
+        returns,
+        id_targets: checker.id_targets.into_inner(),
+    })
+}
+
+/// Names a RETURN item after its leading variable, or its literal value.
+pub(crate) fn return_item_name(tokens: &[Spanned]) -> String {
+    match tokens.first().map(|t| &t.token) {
+        Some(Token::Ident(ident)) | Some(Token::Str(ident)) | Some(Token::Number(ident)) => {
+            ident.clone()
+        }
+        _ => String::new(),
+    }
+}
+
+enum Statement<'a> {
+    Assign(String, &'a [Spanned]),
+    Return(&'a [Spanned]),
+}
+
+/// Splits a query body into assignments and RETURN clauses. Statements that
+/// produce no bindings (`DROP`, `FOR` loops) are skipped.
+fn split_statements(body: &[Spanned]) -> Vec<Statement<'_>> {
+    let mut starts = Vec::new();
+    let mut depth = 0usize;
+    for (i, t) in body.iter().enumerate() {
+        match &t.token {
+            Token::Punct("(" | "{" | "[") => depth += 1,
+            Token::Punct(")" | "}" | "]") => depth = depth.saturating_sub(1),
+            Token::Ident(ident) if depth == 0 => {
+                let assigns = matches!(body.get(i + 1).map(|t| &t.token), Some(Token::Punct("<-")));
+                if assigns || matches!(ident.as_str(), "RETURN" | "DROP" | "FOR") {
+                    starts.push(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut statements = Vec::new();
+    for (n, &start) in starts.iter().enumerate() {
+        let end = starts.get(n + 1).copied().unwrap_or(body.len());
+        let tokens = &body[start..end];
+        match &tokens[0].token {
+            Token::Ident(ident) if ident == "RETURN" => {
+                statements.push(Statement::Return(&tokens[1..]))
+            }
+            Token::Ident(ident) if ident == "DROP" || ident == "FOR" => {}
+            Token::Ident(ident) => statements.push(Statement::Assign(ident.clone(), &tokens[2..])),
+            _ => {}
+        }
+    }
+
+    statements
+}
+
+struct TypeChecker<'a> {
+    schema: &'a HelixSchema,
+    parameters: &'a IndexMap<String, FieldType>,
+    id_targets: RefCell<IndexMap<String, String>>,
+    /// The first type argument naming an undeclared entity.
+    unknown_type: RefCell<Option<String>>,
+    variables: HashMap<String, ReturnType>,
+}
+
+impl TypeChecker<'_> {
+    /// `source::step::step...`
+    fn check(&self, expr: &[Spanned]) -> ReturnType {
+        let mut steps = split_steps(expr).into_iter();
+        let Some(source) = steps.next() else {
+            return ReturnType::Unknown;
+        };
+
+        let mut current = self.check_source(source);
+        for step in steps {
+            if current == ReturnType::Unknown {
+                break;
+            }
+            current = self.check_step(&current, step);
+        }
+        current
+    }
+
+    fn check_source(&self, tokens: &[Spanned]) -> ReturnType {
+        let Some(first) = tokens.first() else {
+            return ReturnType::Unknown;
+        };
+
+        match &first.token {
+            Token::Str(_) => ReturnType::Scalar(FieldType::String),
+            Token::Number(n) if n.contains('.') => {
+                ReturnType::Scalar(FieldType::Float(FloatType::F64))
+            }
+            Token::Number(_) => ReturnType::Scalar(FieldType::Integer(IntegerType::I64)),
+            Token::Ident(ident) => {
+                let (type_arg, args) = generic_call(&tokens[1..]);
+                let has_args = args.is_some_and(|args| !args.is_empty());
+                let lookup = |element: ReturnType| {
+                    if has_args {
+                        element
+                    } else {
+                        ReturnType::Array(Box::new(element))
+                    }
+                };
+
+                if let (Some(name), "N" | "E" | "V") = (&type_arg, ident.as_str()) {
+                    self.note_id_argument(args, name);
+                }
+
+                match (ident.as_str(), type_arg) {
+                    ("N" | "E" | "V", Some(name)) => match self.element(ident, name) {
+                        ReturnType::Unknown => ReturnType::Unknown,
+                        element => lookup(element),
+                    },
+                    ("AddN" | "UpsertN" | "AddE" | "UpsertE" | "AddV" | "UpsertV", Some(name)) => {
+                        self.element(ident, name)
+                    }
+                    ("SearchV" | "SearchBM25", Some(name)) => match self.element(ident, name) {
+                        ReturnType::Unknown => ReturnType::Unknown,
+                        element => ReturnType::Array(Box::new(element)),
+                    },
+                    (_, None) if tokens.len() == 1 => match ident.as_str() {
+                        "true" | "false" => ReturnType::Scalar(FieldType::Boolean),
+                        _ => self.variable(ident).cloned().unwrap_or(ReturnType::Unknown),
+                    },
+                    _ => ReturnType::Unknown,
+                }
+            }
+            _ => ReturnType::Unknown,
+        }
+    }
+
+    fn check_step(&self, current: &ReturnType, tokens: &[Spanned]) -> ReturnType {
+        match tokens.first().map(|t| &t.token) {
+            Some(Token::Punct("{")) => map_element(current, |element| {
+                self.project(element, &tokens[1..tokens.len().saturating_sub(1)])
+            }),
+            Some(Token::Punct("!")) => map_element(current, |element| {
+                let excluded: Vec<String> = tokens
+                    .iter()
+                    .filter_map(|t| match &t.token {
+                        Token::Ident(ident) => Some(ident.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                match self.properties(element) {
+                    Some(properties) => ReturnType::Object(
+                        [("id".to_string(), ReturnType::Scalar(Self::id_of(element)))]
+                            .into_iter()
+                            .chain(properties.iter().map(|(name, field_type)| {
+                                (name.clone(), ReturnType::Scalar(field_type.clone()))
+                            }))
+                            .filter(|(name, _)| !excluded.contains(name))
+                            .map(|(name, return_type)| ReturnField { name, return_type })
+                            .collect(),
+                    ),
+                    None => ReturnType::Unknown,
+                }
+            }),
+            Some(Token::Ident(ident)) => {
+                let (type_arg, args) = generic_call(&tokens[1..]);
+                if let ("From" | "To", ReturnType::Edge(edge)) =
+                    (ident.as_str(), element_of(current))
+                    && let Some(edge) = self.schema.edges.get(edge)
+                {
+                    let endpoint = match ident.as_str() {
+                        "From" => &edge.from_node,
+                        _ => &edge.to_node,
+                    };
+                    self.note_id_argument(args, endpoint);
+                }
+
+                match (ident.as_str(), type_arg) {
+                    ("Out", Some(edge)) => self.traverse(current, &edge, |e| &e.to_node),
+                    ("In", Some(edge)) => self.traverse(current, &edge, |e| &e.from_node),
+                    ("OutE" | "InE", Some(edge)) => match self.element("E", edge) {
+                        ReturnType::Unknown => ReturnType::Unknown,
+                        edge => ReturnType::Array(Box::new(edge)),
+                    },
+                    ("FromN" | "FromV", None) => self.edge_endpoint(current, |e| &e.from_node),
+                    ("ToN" | "ToV", None) => self.edge_endpoint(current, |e| &e.to_node),
+                    ("COUNT", None) => ReturnType::Scalar(FieldType::Integer(IntegerType::U64)),
+                    ("FIRST", None) => match current {
+                        ReturnType::Array(inner) => (**inner).clone(),
+                        other => other.clone(),
+                    },
+                    (
+                        "WHERE" | "RANGE" | "ORDER" | "DEDUP" | "UPDATE" | "Update" | "From" | "To",
+                        _,
+                    ) => current.clone(),
+                    ("ID", None) => {
+                        map_element(current, |element| ReturnType::Scalar(Self::id_of(element)))
+                    }
+                    (property, None) => map_element(current, |element| {
+                        match self.property_type(element, property) {
+                            Some(return_type) => return_type,
+                            None => ReturnType::Unknown,
+                        }
+                    }),
+                    _ => ReturnType::Unknown,
+                }
+            }
+            _ => ReturnType::Unknown,
+        }
+    }
+
+    /// The ID type of an element: specific to its entity when known.
+    fn id_of(element: &ReturnType) -> FieldType {
+        match element {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                FieldType::EntityID(name.clone())
+            }
+            _ => FieldType::ID,
+        }
+    }
+
+    /// Records that a lone `ID` parameter passed as `args` identifies `entity`.
+    fn note_id_argument(&self, args: Option<&[Spanned]>, entity: &str) {
+        if let Some(
+            [
+                Spanned {
+                    token: Token::Ident(param),
+                    ..
+                },
+            ],
+        ) = args
+            && let Some((param, FieldType::ID)) = self.parameter(param)
+        {
+            self.id_targets
+                .borrow_mut()
+                .insert(param.to_string(), entity.to_string());
+        }
+    }
+
+    fn parameter(&self, name: &str) -> Option<(&str, &FieldType)> {
+        self.parameters
+            .get_key_value(name)
+            .map(|(name, field_type)| (name.as_str(), field_type))
+    }
+
+    fn variable(&self, name: &str) -> Option<&ReturnType> {
+        self.variables.get(name)
+    }
+
+    /// `::Out<Edge>` / `::In<Edge>` always yields a list of the edge's endpoint.
+    fn traverse(
+        &self,
+        current: &ReturnType,
+        edge: &str,
+        endpoint: impl Fn(&EdgeDefinition) -> &String,
+    ) -> ReturnType {
+        let Some(edge) = self.schema.edges.get(edge) else {
+            self.element("E", edge.to_string());
+            return ReturnType::Unknown;
+        };
+        if !matches!(
+            element_of(current),
+            ReturnType::Node(_) | ReturnType::Vector(_)
+        ) {
+            return ReturnType::Unknown;
+        }
+        match self.entity(endpoint(edge)) {
+            ReturnType::Unknown => ReturnType::Unknown,
+            target => ReturnType::Array(Box::new(target)),
+        }
+    }
+
+    fn edge_endpoint(
+        &self,
+        current: &ReturnType,
+        endpoint: impl Fn(&EdgeDefinition) -> &String,
+    ) -> ReturnType {
+        map_element(current, |element| match element {
+            ReturnType::Edge(name) => match self.schema.edges.get(name) {
+                Some(edge) => self.entity(endpoint(edge)),
+                None => ReturnType::Unknown,
+            },
+            _ => ReturnType::Unknown,
+        })
+    }
+
+    /// The node, edge or vector named by a step's type argument (`N<User>`,
+    /// `AddE<Knows>`, `SearchV<Doc>`), or `Unknown` if the schema does not
+    /// declare it, which is recorded.
+    fn element(&self, step: &str, name: String) -> ReturnType {
+        let (declared, element) = match step {
+            "E" | "AddE" | "UpsertE" => (
+                self.schema.edges.contains_key(&name),
+                ReturnType::Edge(name.clone()),
+            ),
+            "V" | "AddV" | "UpsertV" | "SearchV" => (
+                self.schema.vectors.contains_key(&name),
+                ReturnType::Vector(name.clone()),
+            ),
+            _ => (
+                self.schema.nodes.contains_key(&name),
+                ReturnType::Node(name.clone()),
+            ),
+        };
+        if declared {
+            element
+        } else {
+            self.unknown_type.borrow_mut().get_or_insert(name);
+            ReturnType::Unknown
+        }
+    }
+
+    /// Resolves an edge endpoint name to a node or vector.
+    fn entity(&self, name: &str) -> ReturnType {
+        if self.schema.nodes.contains_key(name) {
+            ReturnType::Node(name.to_string())
+        } else if self.schema.vectors.contains_key(name) {
+            ReturnType::Vector(name.to_string())
+        } else {
+            ReturnType::Unknown
+        }
+    }
+
+    fn properties(&self, element: &ReturnType) -> Option<&IndexMap<String, FieldType>> {
+        match element {
+            ReturnType::Node(name) => self.schema.nodes.get(name).map(|n| &n.properties),
+            ReturnType::Vector(name) => self.schema.vectors.get(name).map(|v| &v.properties),
+            ReturnType::Edge(name) => self.schema.edges.get(name).map(|e| &e.properties),
+            _ => None,
+        }
+    }
+
+    fn property_type(&self, element: &ReturnType, property: &str) -> Option<ReturnType> {
+        if property == "id" {
+            return Some(ReturnType::Scalar(Self::id_of(element)));
+        }
+        self.properties(element)?
+            .get(property)
+            .map(|field_type| ReturnType::Scalar(field_type.clone()))
+    }
+
+    /// `::{name, alias: traversal, ...}`
+    fn project(&self, element: &ReturnType, fields: &[Spanned]) -> ReturnType {
+        let mut projected = Vec::new();
+        for field in split_top_level(fields) {
+            let Some(Token::Ident(name)) = field.first().map(|t| &t.token) else {
+                return ReturnType::Unknown;
+            };
+            let name = name.clone();
+            let return_type = if field.len() == 1 {
+                self.property_type(element, &name)
+                    .unwrap_or(ReturnType::Unknown)
+            } else if matches!(field.get(1).map(|t| &t.token), Some(Token::Punct(":"))) {
+                self.check_projection_value(element, &field[2..])
+            } else {
+                ReturnType::Unknown
+            };
+            projected.push(ReturnField { name, return_type });
+        }
+        ReturnType::Object(projected)
+    }
+
+    /// The value of `alias: ...` inside a projection, relative to the
+    /// projected element (`_::Out<Knows>`) or a bare property name.
+    fn check_projection_value(&self, element: &ReturnType, tokens: &[Spanned]) -> ReturnType {
+        match tokens.first().map(|t| &t.token) {
+            Some(Token::Punct("_")) => {
+                let mut current = element.clone();
+                for step in split_steps(tokens).into_iter().skip(1) {
+                    current = self.check_step(&current, step);
+                }
+                current
+            }
+            Some(Token::Ident(ident)) if tokens.len() == 1 => self
+                .property_type(element, ident)
+                .unwrap_or(ReturnType::Unknown),
+            _ => self.check(tokens),
+        }
+    }
+}
+
+/// Splits an expression on top-level `::` separators.
+fn split_steps(expr: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut steps = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, t) in expr.iter().enumerate() {
+        match t.token {
+            Token::Punct("(" | "{" | "[") => depth += 1,
+            Token::Punct(")" | "}" | "]") => depth = depth.saturating_sub(1),
+            Token::Punct("::") if depth == 0 => {
+                steps.push(&expr[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    steps.push(&expr[start..]);
+    steps
+}
+
+/// Reads the `<Type>` and `(args)` following a step name.
+fn generic_call(tokens: &[Spanned]) -> (Option<String>, Option<&[Spanned]>) {
+    let mut rest = tokens;
+    let mut type_arg = None;
+    if let [
+        Spanned {
+            token: Token::Punct("<"),
+            ..
+        },
+        Spanned {
+            token: Token::Ident(name),
+            ..
+        },
+        Spanned {
+            token: Token::Punct(">"),
+            ..
+        },
+        tail @ ..,
+    ] = rest
+    {
+        type_arg = Some(name.clone());
+        rest = tail;
+    }
+
+    let args = match rest.first().map(|t| &t.token) {
+        Some(Token::Punct("(")) => Some(&rest[1..rest.len().saturating_sub(1)]),
+        _ => None,
+    };
+    (type_arg, args)
+}
+
+fn element_of(return_type: &ReturnType) -> &ReturnType {
+    match return_type {
+        ReturnType::Array(inner) => inner,
+        other => other,
+    }
+}
+
+/// Applies `f` to each element of a list, or to the value itself.
+fn map_element(return_type: &ReturnType, f: impl Fn(&ReturnType) -> ReturnType) -> ReturnType {
+    match return_type {
+        ReturnType::Array(inner) => ReturnType::Array(Box::new(f(inner))),
+        other => f(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = "
+        N::User { Name: String, Age: U8 }
+        V::Doc { Content: String }
+        E::Knows { From: User, To: User, Properties: { Since: I32 } }
+    ";
+
+    fn parse(queries: &str) -> Result<HelixSchema> {
+        HelixQLSchemaParser::parse_sources(&[
+            (PathBuf::from("schema.hx"), SCHEMA.to_string()),
+            (PathBuf::from("queries.hx"), queries.to_string()),
+        ])
+    }
+
+    /// The inferred RETURN fields of the only query in `source`.
+    fn returns(source: &str) -> Vec<(String, ReturnType)> {
+        let schema = parse(source).unwrap();
+        let query = schema.queries.values().next().unwrap();
+        (query.returns.iter())
+            .map(|field| (field.name.clone(), field.return_type.clone()))
+            .collect()
+    }
+
+    fn node(name: &str) -> ReturnType {
+        ReturnType::Node(name.to_string())
+    }
+
+    fn array(element: ReturnType) -> ReturnType {
+        ReturnType::Array(Box::new(element))
+    }
+
+    fn scalar(field_type: FieldType) -> ReturnType {
+        ReturnType::Scalar(field_type)
+    }
+
+    #[test]
+    fn lookups_by_id_return_one_element() {
+        let schema = parse(
+            "QUERY Get(user_id: ID) =>
+                 user <- N<User>(user_id)
+                 all <- N<User>
+                 RETURN user, all",
+        )
+        .unwrap();
+        let query = &schema.queries["Get"];
+        assert_eq!(query.returns[0].return_type, node("User"));
+        assert_eq!(query.returns[1].return_type, array(node("User")));
+        assert_eq!(
+            query.parameters["user_id"],
+            FieldType::EntityID("User".to_string())
+        );
+    }
+
+    #[test]
+    fn traversals_follow_edge_endpoints() {
+        assert_eq!(
+            returns(
+                "QUERY Friends(id: ID) =>
+                     friends <- N<User>(id)::Out<Knows>
+                     edges <- N<User>(id)::OutE<Knows>
+                     firsts <- edges::FromN
+                     RETURN friends, edges, firsts"
+            ),
+            vec![
+                ("friends".to_string(), array(node("User"))),
+                (
+                    "edges".to_string(),
+                    array(ReturnType::Edge("Knows".to_string()))
+                ),
+                ("firsts".to_string(), array(node("User"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn projections_and_exclusions_build_objects() {
+        let fields = returns(
+            "QUERY Proj() =>
+                 users <- N<User>
+                 RETURN users::{Name, friends: _::Out<Knows>::COUNT}, users::!{Age}",
+        );
+        assert_eq!(
+            fields[0].1,
+            array(ReturnType::Object(vec![
+                ReturnField {
+                    name: "Name".to_string(),
+                    return_type: scalar(FieldType::String),
+                },
+                ReturnField {
+                    name: "friends".to_string(),
+                    return_type: scalar(FieldType::Integer(IntegerType::U64)),
+                },
+            ]))
+        );
+        assert_eq!(
+            fields[1].1,
+            array(ReturnType::Object(vec![
+                ReturnField {
+                    name: "id".to_string(),
+                    return_type: scalar(FieldType::EntityID("User".to_string())),
+                },
+                ReturnField {
+                    name: "Name".to_string(),
+                    return_type: scalar(FieldType::String),
+                },
+            ]))
+        );
+    }
+
+    #[test]
+    fn properties_literals_and_first() {
+        assert_eq!(
+            returns(
+                "QUERY Misc() =>
+                     names <- N<User>::Name
+                     first <- N<User>::FIRST
+                     docs <- SearchV<Doc>(vec, 5)
+                     RETURN names, first, docs, \"ok\", 1"
+            ),
+            vec![
+                ("names".to_string(), array(scalar(FieldType::String))),
+                ("first".to_string(), node("User")),
+                (
+                    "docs".to_string(),
+                    array(ReturnType::Vector("Doc".to_string()))
+                ),
+                ("ok".to_string(), scalar(FieldType::String)),
+                (
+                    "1".to_string(),
+                    scalar(FieldType::Integer(IntegerType::I64))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_variables_stay_unknown() {
+        assert_eq!(
+            returns("QUERY Q() =>\n x <- N<User>\n RETURN y"),
+            vec![("y".to_string(), ReturnType::Unknown)]
+        );
+    }
+
+    #[test]
+    fn undeclared_type_arguments_are_errors() {
+        for body in [
+            "bad <- N<Ghost>",
+            "bad <- AddE<Ghost>",
+            "bad <- SearchV<Ghost>(vec, 5)",
+            "bad <- N<User>::Out<Ghost>",
+            "bad <- N<User>::OutE<Ghost>",
+        ] {
+            let error = parse(&format!("QUERY Bad() =>\n {body}\n RETURN bad")).unwrap_err();
+            match error {
+                Error::UnknownType { name, context } => {
+                    assert_eq!((name.as_str(), context.as_str()), ("Ghost", "query `Bad`"))
+                }
+                other => panic!("{body}: expected UnknownType, got {other:?}"),
+            }
+        }
+    }
+}
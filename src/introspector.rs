@@ -1,6 +1,9 @@
 use crate::error::{Error, Result};
+use crate::inference::infer_query;
+use crate::parser::tokenize;
 use crate::schema::*;
-use crate::utils::to_snake_case;
+use indexmap::IndexMap;
+use log::{debug, trace};
 use std::collections::HashMap;
 
 pub struct HelixDBSchemaIntrospector {
@@ -16,126 +19,157 @@ impl HelixDBSchemaIntrospector {
 
     pub async fn introspect_schema(&self) -> Result<HelixSchema> {
         let url = format!("{}/introspect", self.connection.url);
-        let request = self.client.get(&url);
-
-        println!("Fetching schema from: {url}");
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            return Err(Error::Reqwest(response.error_for_status().unwrap_err()));
+        let request = self
+            .client
+            .get(&url)
+            .headers(self.connection.auth.header_map()?);
+
+        debug!("Fetching schema from: {url}");
+        let response = request.send().await.map_err(|source| Error::Connection {
+            url: url.clone(),
+            source,
+        })?;
+
+        let status = response.status();
+        let response_text = response.text().await?;
+        if !status.is_success() {
+            return Err(Error::Status {
+                url,
+                status,
+                body: response_text,
+            });
         }
+        trace!("Raw response: {response_text}");
 
-        let response_text = response.text().await?;
-        println!("Raw response: {response_text}");
-
-        let introspection: IntrospectionResponse =
-            serde_json::from_str(&response_text).map_err(|e| {
-                Error::IO(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("Failed to parse introspection response: {e}"),
-                ))
-            })?;
+        let introspection: IntrospectionResponse = serde_path_to_error::deserialize(
+            &mut serde_json::Deserializer::from_str(&response_text),
+        )
+        .map_err(|e| Error::Introspection {
+            path: e.path().to_string(),
+            message: e.into_inner().to_string(),
+        })?;
 
-        let mut schema = HelixSchema {
-            nodes: HashMap::new(),
-            vectors: HashMap::new(),
-            queries: HashMap::new(),
-            edges: HashMap::new(),
-        };
+        let mut schema = HelixSchema::default();
 
         // Convert nodes
-        for node_info in introspection.schema.nodes {
+        for (i, node_info) in introspection.schema.nodes.into_iter().enumerate() {
+            let (properties, defaults) = self.convert_properties(
+                &node_info.properties,
+                &format!("schema.nodes[{i}].properties"),
+            )?;
             let node_def = NodeDefinition {
                 name: node_info.name.clone(),
-                properties: self.convert_properties(&node_info.properties)?,
+                properties,
+                defaults,
             };
             schema.nodes.insert(node_info.name, node_def);
         }
 
         // Convert vectors
-        for vector_info in introspection.schema.vectors {
+        for (i, vector_info) in introspection.schema.vectors.into_iter().enumerate() {
+            let (properties, defaults) = self.convert_properties(
+                &vector_info.properties,
+                &format!("schema.vectors[{i}].properties"),
+            )?;
             let vector_def = VectorDefinition {
                 name: vector_info.name.clone(),
-                properties: self.convert_properties(&vector_info.properties)?,
+                properties,
+                defaults,
             };
             schema.vectors.insert(vector_info.name, vector_def);
         }
 
         // Convert edges
-        for edge_info in introspection.schema.edges {
+        for (i, edge_info) in introspection.schema.edges.into_iter().enumerate() {
+            let (properties, defaults) = self.convert_properties(
+                &edge_info.properties,
+                &format!("schema.edges[{i}].properties"),
+            )?;
             let edge_def = EdgeDefinition {
                 name: edge_info.name.clone(),
                 from_node: edge_info.from,
                 to_node: edge_info.to,
-                properties: self.convert_properties(&edge_info.properties)?,
+                properties,
+                defaults,
             };
             schema.edges.insert(edge_info.name, edge_def);
         }
 
         // Convert queries
-        for query_info in introspection.queries {
-            let query_def = QueryDefinition {
+        for (i, query_info) in introspection.queries.into_iter().enumerate() {
+            let mut query_def = QueryDefinition {
                 name: query_info.name.clone(),
-                parameters: self.convert_properties(&query_info.parameters)?,
-                returns: if query_info.returns.len() == 1 {
-                    query_info.returns[0].clone()
-                } else {
-                    format!("{{ {} }}", query_info.returns.join(", "))
-                },
+                parameters: self
+                    .convert_properties(
+                        &query_info.parameters,
+                        &format!("queries[{i}].parameters"),
+                    )?
+                    .0,
+                returns: query_info
+                    .returns
+                    .into_iter()
+                    .map(|name| ReturnField {
+                        name,
+                        return_type: ReturnType::Unknown,
+                    })
+                    .collect(),
                 description: None,
             };
+
+            // Return types can only be inferred when the server sends query bodies
+            if let Some(body) = query_info.body.as_deref()
+                && let Ok(tokens) = tokenize(body)
+            {
+                infer_query(&schema, &query_def, &tokens)?.apply_to(&mut query_def);
+            }
             schema.queries.insert(query_info.name, query_def);
         }
 
+        schema.sort();
+        schema.validate()?;
+
         Ok(schema)
     }
 
+    /// Property types and defaults. `path` locates `properties` in the
+    /// response, for error messages.
     fn convert_properties(
         &self,
-        properties: &HashMap<String, String>,
-    ) -> Result<HashMap<String, FieldType>> {
-        let mut result = HashMap::new();
-
-        for (prop_name, type_str) in properties {
-            let field_type = Self::parse_field_type(type_str)?;
-            result.insert(to_snake_case(prop_name), field_type);
-        }
-
-        Ok(result)
-    }
-
-    fn parse_field_type(type_str: &str) -> Result<FieldType> {
-        match type_str {
-            "String" => Ok(FieldType::String),
-            "I8" => Ok(FieldType::Integer),
-            "I16" => Ok(FieldType::Integer),
-            "I32" => Ok(FieldType::Integer),
-            "I64" => Ok(FieldType::Integer),
-            "I128" => Ok(FieldType::Integer),
-            "U8" => Ok(FieldType::Integer),
-            "U16" => Ok(FieldType::Integer),
-            "U32" => Ok(FieldType::Integer),
-            "U64" => Ok(FieldType::Integer),
-            "U128" => Ok(FieldType::Integer),
-            "F64" => Ok(FieldType::Float),
-            "Boolean" => Ok(FieldType::Boolean),
-            "ID" => Ok(FieldType::Integer),
-            s if s.starts_with("Array(") && s.ends_with(")") => {
-                let inner_type = &s[6..s.len() - 1];
-                let inner = Self::parse_field_type(inner_type)?;
-                Ok(FieldType::Array(Box::new(inner)))
+        properties: &HashMap<String, PropertyInfo>,
+        path: &str,
+    ) -> Result<(IndexMap<String, FieldType>, IndexMap<String, DefaultValue>)> {
+        let mut result = IndexMap::new();
+        let mut defaults = IndexMap::new();
+
+        for (prop_name, info) in properties {
+            let (type_str, optional, default) = match info {
+                PropertyInfo::Type(type_str) => (type_str, false, None),
+                PropertyInfo::Detailed {
+                    type_name,
+                    optional,
+                    default,
+                } => (type_name, *optional, default.as_ref()),
+            };
+            let mut field_type = FieldType::parse(type_str).map_err(|e| Error::Introspection {
+                path: format!("{path}.{prop_name}"),
+                message: e.to_string(),
+            })?;
+            if optional && !matches!(field_type, FieldType::Optional(_)) {
+                field_type = FieldType::Optional(Box::new(field_type));
             }
-            s if s.starts_with("Vector<") && s.ends_with(">") => {
-                let dim_str = &s[7..s.len() - 1];
-                let dim = dim_str.parse::<usize>().map_err(|e| {
-                    Error::IO(std::io::Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        format!("Invalid vector dimension '{dim_str}': {e}"),
-                    ))
-                })?;
-                Ok(FieldType::Vector(dim))
+            result.insert(prop_name.clone(), field_type);
+
+            if let Some(default) = default {
+                let default = match default {
+                    serde_json::Value::String(value) if value == "NOW" => DefaultValue::Now,
+                    value => DefaultValue::Literal(value.to_string()),
+                };
+                defaults.insert(prop_name.clone(), default);
             }
-            _ => Ok(FieldType::Custom(type_str.to_string())),
         }
+        result.sort_keys();
+        defaults.sort_keys();
+
+        Ok((result, defaults))
     }
 }
@@ -1,13 +1,33 @@
 extern crate self as helix_ts_gen;
 
+pub mod cli;
+pub mod config;
 pub mod connection;
+pub mod diff;
 pub mod error;
+pub mod generator;
+pub mod identifier;
+mod inference;
 pub mod introspector;
+pub mod parser;
+pub mod py_generator;
+pub mod rs_generator;
 pub mod schema;
+pub mod snapshot;
 pub mod ts_generator;
-pub mod utils;
+pub mod zod_generator;
 
-pub use connection::HelixDBConnection;
+pub use connection::{Auth, HelixDBConnection};
+pub use generator::{Generator, GeneratorOptions, GeneratorRegistry, OutputFile};
+pub use identifier::NamingStrategy;
 pub use introspector::HelixDBSchemaIntrospector;
+pub use parser::HelixQLSchemaParser;
+pub use py_generator::{PythonGenerator, PythonOptions};
+pub use rs_generator::{RustGenerator, RustOptions};
 pub use schema::*;
-pub use ts_generator::TypeScriptGenerator;
+pub use snapshot::Snapshot;
+pub use ts_generator::{
+    ClientKind, DateRepresentation, LargeIntegerPolicy, Layout, TypeScriptGenerator,
+    TypeScriptOptions,
+};
+pub use zod_generator::ZodGenerator;
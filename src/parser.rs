@@ -0,0 +1,745 @@
+use crate::error::{Error, Result};
+use crate::inference::{infer_query, return_item_name};
+use crate::schema::*;
+use indexmap::IndexMap;
+use log::debug;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PUNCTUATION: &[&str] = &[
+    "::", "<-", "=>", "==", "!=", "<=", ">=", "(", ")", "{", "}", "[", "]", "<", ">", ",", ":",
+    "!", ".", "=", "+", "-", "*", "/", ";", "|", "&", "?", "_",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Ident(String),
+    Number(String),
+    Str(String),
+    Punct(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Spanned {
+    pub token: Token,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Splits HelixQL source into tokens, dropping whitespace and `//` comments.
+pub(crate) fn tokenize(source: &str) -> std::result::Result<Vec<Spanned>, (usize, usize, String)> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let (mut i, mut line, mut column) = (0, 1, 1);
+
+    while i < chars.len() {
+        let c = chars[i];
+        let (start, start_line, start_column) = (i, line, column);
+
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            column = 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            column += 1;
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let token = if c.is_alphabetic()
+            || (c == '_' && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric()))
+        {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            Token::Number(chars[start..i].iter().collect())
+        } else if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\n' {
+                    return Err((
+                        start_line,
+                        start_column,
+                        "unterminated string literal".to_string(),
+                    ));
+                }
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err((
+                    start_line,
+                    start_column,
+                    "unterminated string literal".to_string(),
+                ));
+            }
+            let value = chars[start + 1..i].iter().collect();
+            i += 1;
+            Token::Str(value)
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            match PUNCTUATION.iter().find(|p| rest.starts_with(**p)) {
+                Some(p) => {
+                    i += p.len();
+                    Token::Punct(p)
+                }
+                None => {
+                    return Err((
+                        start_line,
+                        start_column,
+                        format!("unexpected character '{c}'"),
+                    ));
+                }
+            }
+        };
+
+        column += i - start;
+        tokens.push(Spanned {
+            token,
+            line: start_line,
+            column: start_column,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Builds a `HelixSchema` from the `.hx` files of a `helixdb-cfg` directory,
+/// without needing a running HelixDB instance.
+pub struct HelixQLSchemaParser {
+    schema_dir: PathBuf,
+}
+
+impl HelixQLSchemaParser {
+    pub fn new(schema_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            schema_dir: schema_dir.into(),
+        }
+    }
+
+    pub fn parse_schema(&self) -> Result<HelixSchema> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&self.schema_dir)
+            .map_err(Error::file(&self.schema_dir))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "hx"))
+            .collect();
+        files.sort();
+
+        if files.is_empty() {
+            return Err(Error::NoSchemaFiles(self.schema_dir.clone()));
+        }
+
+        let mut sources = Vec::new();
+        for path in files {
+            debug!("Parsing {}", path.display());
+            let source = fs::read_to_string(&path).map_err(Error::file(&path))?;
+            sources.push((path, source));
+        }
+        Self::parse_sources(&sources)
+    }
+
+    /// Builds a schema from `.hx` sources given with their paths, which are
+    /// only used in error messages.
+    pub(crate) fn parse_sources(sources: &[(PathBuf, String)]) -> Result<HelixSchema> {
+        let mut schema = HelixSchema::default();
+
+        let mut bodies = Vec::new();
+        let mut definitions = Definitions::new();
+        for (path, source) in sources {
+            bodies.extend(Self::parse_source(
+                path,
+                source,
+                &mut schema,
+                &mut definitions,
+            )?);
+        }
+
+        for (name, body) in bodies {
+            let inferred = infer_query(&schema, &schema.queries[&name], &body)?;
+            if let Some(query) = schema.queries.get_mut(&name) {
+                inferred.apply_to(query);
+            }
+        }
+
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    /// Parses a single HelixQL source file, adding its definitions to `schema`
+    /// and returning the body of each query it declares.
+    fn parse_source(
+        path: &Path,
+        source: &str,
+        schema: &mut HelixSchema,
+        definitions: &mut Definitions,
+    ) -> Result<Vec<(String, Vec<Spanned>)>> {
+        let tokens = tokenize(source)
+            .map_err(|(line, column, message)| parse_error(path, source, line, column, &message))?;
+        let mut parser = Parser {
+            path,
+            source,
+            tokens,
+            pos: 0,
+        };
+
+        let mut bodies = Vec::new();
+        while !parser.at_end() {
+            let start = parser.pos;
+            match parser.peek_ident() {
+                Some("N") => {
+                    let (name, (properties, defaults)) = parser.parse_entity("N")?;
+                    parser.define(definitions, "node", &name, start)?;
+                    let node = NodeDefinition {
+                        name: name.clone(),
+                        properties,
+                        defaults,
+                    };
+                    schema.nodes.insert(name, node);
+                }
+                Some("V") => {
+                    let (name, (properties, defaults)) = parser.parse_entity("V")?;
+                    parser.define(definitions, "vector", &name, start)?;
+                    let vector = VectorDefinition {
+                        name: name.clone(),
+                        properties,
+                        defaults,
+                    };
+                    schema.vectors.insert(name, vector);
+                }
+                Some("E") => {
+                    let edge = parser.parse_edge()?;
+                    parser.define(definitions, "edge", &edge.name, start)?;
+                    schema.edges.insert(edge.name.clone(), edge);
+                }
+                Some("QUERY") => {
+                    let (query, body) = parser.parse_query()?;
+                    parser.define(definitions, "query", &query.name, start)?;
+                    bodies.push((query.name.clone(), body));
+                    schema.queries.insert(query.name.clone(), query);
+                }
+                _ => return Err(parser.error("expected `N::`, `V::`, `E::` or `QUERY`")),
+            }
+        }
+
+        Ok(bodies)
+    }
+}
+
+fn parse_error(path: &Path, source: &str, line: usize, column: usize, message: &str) -> Error {
+    Error::Parse {
+        path: path.to_path_buf(),
+        line,
+        column,
+        message: message.to_string(),
+        source_line: source
+            .lines()
+            .nth(line.saturating_sub(1))
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// Where each node, vector, edge and query was defined (`path:line:column`),
+/// by kind and name.
+type Definitions = HashMap<(&'static str, String), String>;
+
+/// Property types and their defaults, by name.
+type Fields = (IndexMap<String, FieldType>, IndexMap<String, DefaultValue>);
+
+struct Parser<'a> {
+    path: &'a Path,
+    source: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|t| &t.token)
+    }
+
+    fn peek_ident(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => Some(ident),
+            _ => None,
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> Error {
+        match self.tokens.get(pos).or(self.tokens.last()) {
+            Some(t) => parse_error(self.path, self.source, t.line, t.column, message),
+            None => parse_error(self.path, self.source, 1, 1, message),
+        }
+    }
+
+    /// Records the definition starting at token `pos`, rejecting another
+    /// one of the same kind and name in any file.
+    fn define(
+        &self,
+        definitions: &mut Definitions,
+        kind: &'static str,
+        name: &str,
+        pos: usize,
+    ) -> Result<()> {
+        match definitions.entry((kind, name.to_string())) {
+            Entry::Occupied(first) => Err(self.error_at(
+                pos,
+                &format!("{kind} `{name}` is already defined at {}", first.get()),
+            )),
+            Entry::Vacant(entry) => {
+                let token = &self.tokens[pos];
+                entry.insert(format!(
+                    "{}:{}:{}",
+                    self.path.display(),
+                    token.line,
+                    token.column
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_ident() == Some(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{punct}`")))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{keyword}`")))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// `N::Name { Field: Type, ... }` and `V::Name { ... }`
+    fn parse_entity(&mut self, kind: &str) -> Result<(String, Fields)> {
+        self.expect_keyword(kind)?;
+        self.expect_punct("::")?;
+        let name = self.expect_ident()?;
+        let fields = self.parse_fields()?;
+        Ok((name, fields))
+    }
+
+    /// `E::Name { From: Node, To: Node, Properties: { ... } }`
+    fn parse_edge(&mut self) -> Result<EdgeDefinition> {
+        self.expect_keyword("E")?;
+        self.expect_punct("::")?;
+        let name = self.expect_ident()?;
+        self.eat_keyword("UNIQUE");
+        self.expect_punct("{")?;
+
+        let (mut from_node, mut to_node) = (None, None);
+        let (mut properties, mut defaults) = (IndexMap::new(), IndexMap::new());
+        while !self.eat_punct("}") {
+            let key = self.expect_ident()?;
+            self.expect_punct(":")?;
+            match key.as_str() {
+                "From" => from_node = Some(self.expect_ident()?),
+                "To" => to_node = Some(self.expect_ident()?),
+                "Properties" => (properties, defaults) = self.parse_fields()?,
+                _ => return Err(self.error(&format!("unknown edge attribute `{key}`"))),
+            }
+            if !self.eat_punct(",") {
+                self.expect_punct("}")?;
+                break;
+            }
+        }
+
+        let from_node =
+            from_node.ok_or_else(|| self.error(&format!("edge `{name}` is missing `From`")))?;
+        let to_node =
+            to_node.ok_or_else(|| self.error(&format!("edge `{name}` is missing `To`")))?;
+
+        Ok(EdgeDefinition {
+            name,
+            from_node,
+            to_node,
+            properties,
+            defaults,
+        })
+    }
+
+    /// `{ [INDEX] Field: Type [DEFAULT value], ... }`
+    fn parse_fields(&mut self) -> Result<Fields> {
+        let (mut fields, mut defaults) = (IndexMap::new(), IndexMap::new());
+        self.expect_punct("{")?;
+
+        while !self.eat_punct("}") {
+            self.eat_keyword("UNIQUE");
+            self.eat_keyword("INDEX");
+            let name = self.expect_ident()?;
+            self.expect_punct(":")?;
+            let field_type = self.parse_type()?;
+            if self.eat_keyword("DEFAULT") {
+                defaults.insert(name.clone(), self.parse_default_value()?);
+            }
+            fields.insert(name, field_type);
+
+            if !self.eat_punct(",") {
+                self.expect_punct("}")?;
+                break;
+            }
+        }
+
+        Ok((fields, defaults))
+    }
+
+    /// `NOW`, or a literal: `0`, `-1.5`, `"guest"`, `true`.
+    fn parse_default_value(&mut self) -> Result<DefaultValue> {
+        let negative = self.eat_punct("-");
+        let value = match self.peek() {
+            Some(Token::Number(number)) if negative => DefaultValue::Literal(format!("-{number}")),
+            Some(Token::Number(number)) => DefaultValue::Literal(number.clone()),
+            Some(Token::Ident(ident)) if ident == "NOW" && !negative => DefaultValue::Now,
+            Some(Token::Ident(ident)) if !negative => DefaultValue::Literal(ident.clone()),
+            Some(Token::Str(value)) if !negative => DefaultValue::Literal(format!("{value:?}")),
+            _ => return Err(self.error("expected default value")),
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+
+    /// `String`, `U32`, `[F64]`, `String?`, ...
+    fn parse_type(&mut self) -> Result<FieldType> {
+        let field_type = if self.eat_punct("[") {
+            let inner = self.parse_type()?;
+            self.expect_punct("]")?;
+            FieldType::Array(Box::new(inner))
+        } else {
+            let start = self.pos;
+            let name = self.expect_ident()?;
+            FieldType::parse(&name).map_err(|e| self.error_at(start, &e.to_string()))?
+        };
+        if self.eat_punct("?") {
+            return Ok(FieldType::Optional(Box::new(field_type)));
+        }
+        Ok(field_type)
+    }
+
+    /// `QUERY Name(param: Type, ...) => <statements> RETURN a, b`
+    /// Return types are filled in once the whole schema is known.
+    fn parse_query(&mut self) -> Result<(QueryDefinition, Vec<Spanned>)> {
+        self.expect_keyword("QUERY")?;
+        let name = self.expect_ident()?;
+
+        let mut parameters = IndexMap::new();
+        self.expect_punct("(")?;
+        while !self.eat_punct(")") {
+            let param_name = self.expect_ident()?;
+            self.expect_punct(":")?;
+            let field_type = self.parse_type()?;
+            parameters.insert(param_name, field_type);
+            if !self.eat_punct(",") {
+                self.expect_punct(")")?;
+                break;
+            }
+        }
+        self.expect_punct("=>")?;
+
+        let body_start = self.pos;
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Ident(ident) if depth == 0 && ident == "QUERY" => break,
+                // A schema declaration after the query
+                Token::Ident(ident)
+                    if depth == 0
+                        && matches!(ident.as_str(), "N" | "V" | "E")
+                        && self.peek_at(1) == Some(&Token::Punct("::")) =>
+                {
+                    break;
+                }
+                Token::Punct("(" | "{" | "[") => depth += 1,
+                Token::Punct(")" | "}" | "]") => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let body = &self.tokens[body_start..self.pos];
+
+        let return_start = body
+            .iter()
+            .rposition(|t| t.token == Token::Ident("RETURN".to_string()))
+            .ok_or_else(|| self.error(&format!("query `{name}` has no RETURN clause")))?;
+        let returns = split_top_level(&body[return_start + 1..])
+            .into_iter()
+            .map(|item| ReturnField {
+                name: return_item_name(item),
+                return_type: ReturnType::Unknown,
+            })
+            .collect();
+
+        let query = QueryDefinition {
+            name,
+            parameters,
+            returns,
+            description: None,
+        };
+        Ok((query, body.to_vec()))
+    }
+}
+
+/// Splits a token slice on commas that are not nested in brackets.
+pub(crate) fn split_top_level(tokens: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, t) in tokens.iter().enumerate() {
+        match t.token {
+            Token::Punct("(" | "{" | "[") => depth += 1,
+            Token::Punct(")" | "}" | "]") => depth = depth.saturating_sub(1),
+            Token::Punct(",") if depth == 0 => {
+                items.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        items.push(&tokens[start..]);
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<HelixSchema> {
+        HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+    }
+
+    #[test]
+    fn tokenize_skips_comments_and_whitespace() {
+        let tokens = tokenize("N::User // a node\n{ Name: \"x\" }").unwrap();
+        let kinds: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Token::Ident("N".to_string()),
+                Token::Punct("::"),
+                Token::Ident("User".to_string()),
+                Token::Punct("{"),
+                Token::Ident("Name".to_string()),
+                Token::Punct(":"),
+                Token::Str("x".to_string()),
+                Token::Punct("}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_reports_unterminated_strings() {
+        let (line, column, _) = tokenize("N::A {\n  Name: \"oops\n}").unwrap_err();
+        assert_eq!((line, column), (2, 9));
+    }
+
+    #[test]
+    fn parses_entities_optional_fields_and_defaults() {
+        let schema = parse(
+            "N::User { INDEX Name: String, Age: U8 DEFAULT 0, Nick: String?, \
+             Joined: Date DEFAULT NOW, Role: String DEFAULT \"member\", Delta: I32 DEFAULT -1 }
+             V::Doc { Content: String }
+             E::Knows UNIQUE { From: User, To: User, Properties: { Since: I32 } }",
+        )
+        .unwrap();
+
+        let user = &schema.nodes["User"];
+        assert_eq!(user.properties["Name"], FieldType::String);
+        assert_eq!(
+            user.properties["Nick"],
+            FieldType::Optional(Box::new(FieldType::String))
+        );
+        assert_eq!(user.properties["Joined"], FieldType::Date);
+        assert_eq!(user.defaults["Age"], DefaultValue::Literal("0".to_string()));
+        assert_eq!(user.defaults["Joined"], DefaultValue::Now);
+        assert_eq!(
+            user.defaults["Role"],
+            DefaultValue::Literal("\"member\"".to_string())
+        );
+        assert_eq!(
+            user.defaults["Delta"],
+            DefaultValue::Literal("-1".to_string())
+        );
+        assert!(!user.defaults.contains_key("Name"));
+
+        assert!(schema.vectors.contains_key("Doc"));
+        let knows = &schema.edges["Knows"];
+        assert_eq!(
+            (knows.from_node.as_str(), knows.to_node.as_str()),
+            ("User", "User")
+        );
+        assert_eq!(
+            knows.properties["Since"],
+            FieldType::Integer(IntegerType::I32)
+        );
+    }
+
+    #[test]
+    fn query_body_ends_at_next_query() {
+        let schema = parse(
+            "N::A { Name: String }
+             QUERY GetA(id: ID) =>
+                 a <- N<A>(id)
+                 RETURN a
+             QUERY CountA() =>
+                 n <- N<A>::COUNT
+                 RETURN n",
+        )
+        .unwrap();
+        assert_eq!(schema.queries.len(), 2);
+        assert_eq!(
+            schema.queries["GetA"].returns[0].return_type,
+            ReturnType::Node("A".to_string())
+        );
+    }
+
+    #[test]
+    fn query_body_ends_at_schema_declaration() {
+        let schema = parse(
+            "QUERY GetA(id: ID) =>
+                 a <- N<A>(id)
+                 RETURN a
+             N::A { Name: String }
+             E::Likes { From: A, To: A, Properties: {} }",
+        )
+        .unwrap();
+        assert_eq!(schema.nodes.len(), 1);
+        assert_eq!(schema.edges.len(), 1);
+        let returns = &schema.queries["GetA"].returns;
+        assert_eq!(returns.len(), 1);
+        assert_eq!(returns[0].return_type, ReturnType::Node("A".to_string()));
+    }
+
+    #[test]
+    fn query_parameters_accept_optional_types() {
+        let schema = parse(
+            "N::A { Name: String }
+             QUERY Find(name: String?, limit: U32) =>
+                 a <- N<A>
+                 RETURN a",
+        )
+        .unwrap();
+        let parameters = &schema.queries["Find"].parameters;
+        assert_eq!(
+            parameters["name"],
+            FieldType::Optional(Box::new(FieldType::String))
+        );
+        assert_eq!(parameters["limit"], FieldType::Integer(IntegerType::U32));
+    }
+
+    #[test]
+    fn query_without_return_is_an_error() {
+        let error = parse("QUERY Nothing() => x <- N<A>").unwrap_err();
+        assert!(
+            error.to_string().contains("has no RETURN clause"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_token() {
+        match parse("N::User {\n  Name String\n}") {
+            Err(Error::Parse {
+                line,
+                column,
+                message,
+                source_line,
+                ..
+            }) => {
+                assert_eq!((line, column), (2, 8));
+                assert_eq!(message, "expected `:`");
+                assert_eq!(source_line, "  Name String");
+            }
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn duplicate_definitions_name_both_locations() {
+        let error = HelixQLSchemaParser::parse_sources(&[
+            (
+                PathBuf::from("schema.hx"),
+                "N::User { name: String }\nN::Tag { name: String }".to_string(),
+            ),
+            (
+                PathBuf::from("more.hx"),
+                "E::Tagged { From: User, To: Tag }\n  N::User { email: String }".to_string(),
+            ),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "more.hx:2:3: node `User` is already defined at schema.hx:1:1\n  |\n  |   N::User { email: String }\n  |   ^"
+        );
+
+        let query = "QUERY getUser(id: ID) =>\n u <- N<User>(id)\n RETURN u\n";
+        let error = parse(&format!("N::User {{ name: String }}\n{query}{query}")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("schema.hx:5:1: query `getUser` is already defined at schema.hx:2:1")
+        );
+
+        // Each kind has its own names
+        assert!(parse("N::Doc { name: String }\nV::Doc { name: String }").is_ok());
+    }
+}
@@ -0,0 +1,530 @@
+use crate::{
+    error::Result,
+    generator::{Generator, GeneratorOptions, OutputFile},
+    identifier::{
+        Language, Namespace, NamingStrategy, to_pascal_case, to_screaming_snake_case, to_snake_case,
+    },
+    schema::*,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the `python` target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PythonOptions {
+    /// Python types for HelixQL types this tool does not know, by name.
+    pub type_mappings: IndexMap<String, String>,
+    /// Spelling of model fields; the schema's names are kept as aliases.
+    pub property_naming: NamingStrategy,
+}
+
+/// Emits Pydantic models and a typed wrapper around the `helix-py` client.
+pub struct PythonGenerator {
+    options: PythonOptions,
+}
+
+impl PythonGenerator {
+    pub fn new(options: PythonOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Generator for PythonGenerator {
+    fn generate(
+        &self,
+        schema: &HelixSchema,
+        options: &GeneratorOptions,
+    ) -> Result<Vec<OutputFile>> {
+        let emitter = PythonEmitter {
+            schema,
+            options: &self.options,
+        };
+        emitter.check_names()?;
+        Ok(vec![OutputFile {
+            path: options.output.clone(),
+            contents: emitter.generate(),
+        }])
+    }
+}
+
+struct PythonEmitter<'a> {
+    schema: &'a HelixSchema,
+    options: &'a PythonOptions,
+}
+
+impl PythonEmitter<'_> {
+    /// Rejects schemas where distinct names would generate the same class,
+    /// endpoint constant, method or field.
+    fn check_names(&self) -> Result<()> {
+        let mut classes = Namespace::new("Python class names");
+        let entities = (self.schema.nodes.keys().map(|name| ("node", name)))
+            .chain(self.schema.vectors.keys().map(|name| ("vector", name)))
+            .chain(self.schema.edges.keys().map(|name| ("edge", name)));
+        for (kind, name) in entities {
+            classes.claim(
+                &format!("{kind} `{name}`"),
+                Language::Python.type_name(name),
+            )?;
+        }
+        let mut endpoints = Namespace::new("QueryEndpoints");
+        let mut methods = Namespace::new("TypedHelixDBClient");
+        methods.claim("the built-in `query` method", "query")?;
+        for name in self.schema.queries.keys() {
+            let source = format!("query `{name}`");
+            classes.claim(&source, format!("{}Params", to_pascal_case(name)))?;
+            classes.claim(&source, format!("{}Result", to_pascal_case(name)))?;
+            endpoints.claim(&source, to_screaming_snake_case(name))?;
+            methods.claim(&source, Self::identifier(&to_snake_case(name)))?;
+        }
+
+        for (name, node) in &self.schema.nodes {
+            self.check_fields(
+                name,
+                &["id", "created_at", "updated_at"],
+                "property",
+                node.properties.keys(),
+            )?;
+        }
+        for (name, vector) in &self.schema.vectors {
+            self.check_fields(name, &["id"], "property", vector.properties.keys())?;
+        }
+        for (name, edge) in &self.schema.edges {
+            self.check_fields(
+                name,
+                &["id", "created_at", "from", "to"],
+                "property",
+                edge.properties.keys(),
+            )?;
+        }
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            self.check_fields(
+                &format!("{pascal}Params"),
+                &[],
+                "parameter",
+                query.parameters.keys(),
+            )?;
+            let returns = query.returns.iter().map(|field| &field.name);
+            self.check_fields(&format!("{pascal}Result"), &[], "field", returns)?;
+        }
+        Ok(())
+    }
+
+    /// `base` are built-in fields, which a property of the same name
+    /// replaces.
+    fn check_fields<'n>(
+        &self,
+        owner: &str,
+        base: &[&str],
+        kind: &str,
+        fields: impl Iterator<Item = &'n String>,
+    ) -> Result<()> {
+        let fields: Vec<&String> = fields.collect();
+        let mut namespace = Namespace::new(owner);
+        for field in base
+            .iter()
+            .filter(|field| !fields.iter().any(|f| f == *field))
+        {
+            namespace.claim(
+                &format!("built-in field `{field}`"),
+                self.field_identifier(field),
+            )?;
+        }
+        for field in fields {
+            namespace.claim(&format!("{kind} `{field}`"), self.field_identifier(field))?;
+        }
+        Ok(())
+    }
+
+    fn generate(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("# Generated Python types for HelixDB schema\n");
+        output.push_str("# This file is auto-generated. Do not edit manually.\n");
+        output.push_str("# Generated with helix-ts-gen\n\n");
+
+        output.push_str("from __future__ import annotations\n\n");
+        output.push_str("from datetime import datetime\n");
+        output.push_str("from typing import Any, Dict, List, Optional\n\n");
+        output.push_str("from helix.client import Client\n");
+        output.push_str("from pydantic import BaseModel, ConfigDict, Field\n\n");
+
+        output.push_str(&self.generate_utility_types());
+        output.push_str(&self.generate_node_types());
+        output.push_str(&self.generate_vector_types());
+        output.push_str(&self.generate_edge_types());
+        output.push_str(&self.generate_query_types());
+        output.push_str(&self.generate_client());
+
+        output
+    }
+
+    fn generate_utility_types(&self) -> String {
+        r#"# Utility types
+HelixID = str  # UUID
+Vector = List[float]
+
+
+class HelixModel(BaseModel):
+    model_config = ConfigDict(populate_by_name=True)
+
+
+class BaseNode(HelixModel):
+    id: HelixID
+    created_at: Optional[datetime] = None
+    updated_at: Optional[datetime] = None
+
+
+class BaseVector(HelixModel):
+    id: HelixID
+
+
+class BaseEdge(HelixModel):
+    id: Optional[HelixID] = None
+    created_at: Optional[datetime] = None
+
+
+"#
+        .to_string()
+    }
+
+    fn generate_node_types(&self) -> String {
+        let mut output = String::from("# Node types\n");
+        for (name, node) in &self.schema.nodes {
+            output.push_str(&self.model(name, "BaseNode", &[], &node.properties));
+        }
+        output
+    }
+
+    fn generate_vector_types(&self) -> String {
+        let mut output = String::from("# Vector types\n");
+        for (name, vector) in &self.schema.vectors {
+            output.push_str(&self.model(name, "BaseVector", &[], &vector.properties));
+        }
+        output
+    }
+
+    fn generate_edge_types(&self) -> String {
+        let mut output = String::from("# Edge types\n");
+        for (name, edge) in &self.schema.edges {
+            let endpoints = [
+                (
+                    "from".to_string(),
+                    Language::Python.type_name(&edge.from_node),
+                ),
+                ("to".to_string(), Language::Python.type_name(&edge.to_node)),
+            ];
+            output.push_str(&self.model(name, "BaseEdge", &endpoints, &edge.properties));
+        }
+        output
+    }
+
+    fn generate_query_types(&self) -> String {
+        let mut output = String::from("# Query parameter and result types\n");
+
+        for (name, query) in &self.schema.queries {
+            let param_type_name = format!("{}Params", to_pascal_case(name));
+            output.push_str(&self.model(&param_type_name, "HelixModel", &[], &query.parameters));
+
+            let result_fields: Vec<(String, String)> = query
+                .returns
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.clone(),
+                        self.return_type_to_python(&field.return_type),
+                    )
+                })
+                .collect();
+            let result_type_name = format!("{}Result", to_pascal_case(name));
+            output.push_str(&self.model(
+                &result_type_name,
+                "HelixModel",
+                &result_fields,
+                &IndexMap::new(),
+            ));
+        }
+
+        output
+    }
+
+    fn generate_client(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("# Query endpoint constants\n");
+        output.push_str("class QueryEndpoints:\n");
+        if self.schema.queries.is_empty() {
+            output.push_str("    pass\n");
+        }
+        for name in self.schema.queries.keys() {
+            output.push_str(&format!(
+                "    {} = \"{name}\"\n",
+                to_screaming_snake_case(name)
+            ));
+        }
+        output.push_str("\n\n");
+
+        output.push_str("# Client wrapper for type-safe HelixDB operations\n");
+        output.push_str("class TypedHelixDBClient:\n");
+        output.push_str("    \"\"\"\n");
+        output.push_str("    Type-safe wrapper around the helix-py client.\n");
+        output.push_str("    Usage:\n");
+        output.push_str("        client = TypedHelixDBClient(Client(local=True, port=6969))\n");
+        output.push_str("    \"\"\"\n\n");
+        output.push_str("    def __init__(self, client: Client) -> None:\n");
+        output.push_str("        self.client = client\n\n");
+
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            if let Some(ref description) = query.description {
+                output.push_str(&format!("    # {description}\n"));
+            }
+            output.push_str(&format!(
+                "    def {}(self, params: {pascal}Params) -> {pascal}Result:\n",
+                Self::identifier(&to_snake_case(name))
+            ));
+            output.push_str(&format!(
+                "        response = self.client.query(\"{name}\", _payload(params))\n"
+            ));
+            output.push_str(&format!(
+                "        return {pascal}Result.model_validate(_first(response))\n\n"
+            ));
+        }
+
+        output.push_str("    def query(self, endpoint: str, params: Dict[str, Any]) -> Any:\n");
+        output.push_str("        return _first(self.client.query(endpoint, params))\n\n\n");
+
+        output.push_str("def create_typed_client(client: Client) -> TypedHelixDBClient:\n");
+        output.push_str("    return TypedHelixDBClient(client)\n\n\n");
+
+        output.push_str("def _payload(params: HelixModel) -> Dict[str, Any]:\n");
+        output.push_str(
+            "    # Optional params that were not passed are left out; an explicit None is sent as null\n",
+        );
+        output.push_str(
+            "    return params.model_dump(mode=\"json\", by_alias=True, exclude_unset=True)\n\n\n",
+        );
+        output.push_str("def _first(response: Any) -> Any:\n");
+        output.push_str("    # helix-py returns one result per payload sent\n");
+        output.push_str("    if isinstance(response, list) and len(response) == 1:\n");
+        output.push_str("        return response[0]\n");
+        output.push_str("    return response\n");
+
+        output
+    }
+
+    /// A Pydantic model; `extra_fields` are already-mapped Python types that
+    /// precede the schema properties.
+    fn model(
+        &self,
+        name: &str,
+        base: &str,
+        extra_fields: &[(String, String)],
+        properties: &IndexMap<String, FieldType>,
+    ) -> String {
+        let mut output = format!("class {}({base}):\n", Language::Python.type_name(name));
+
+        let fields =
+            extra_fields
+                .iter()
+                .cloned()
+                .chain(properties.iter().map(|(prop_name, field_type)| {
+                    (prop_name.clone(), self.field_type_to_python(field_type))
+                }));
+
+        let mut empty = true;
+        for (field_name, py_type) in fields {
+            empty = false;
+            let identifier = self.field_identifier(&field_name);
+            let optional = py_type.starts_with("Optional[");
+            if identifier == field_name {
+                let default = if optional { " = None" } else { "" };
+                output.push_str(&format!("    {identifier}: {py_type}{default}\n"));
+            } else {
+                let default = if optional { "default=None, " } else { "" };
+                output.push_str(&format!(
+                    "    {identifier}: {py_type} = Field({default}alias=\"{field_name}\")\n"
+                ));
+            }
+        }
+        if empty {
+            output.push_str("    pass\n");
+        }
+
+        output.push_str("\n\n");
+        output
+    }
+
+    /// The attribute for a schema property, parameter or result field.
+    fn field_identifier(&self, name: &str) -> String {
+        Self::identifier(&self.options.property_naming.apply(name))
+    }
+
+    /// Makes a name usable as a Python attribute, e.g. `from` -> `from_`,
+    /// `str` -> `str_`.
+    fn identifier(name: &str) -> String {
+        let mut identifier: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            identifier.insert(0, '_');
+        }
+        if Language::Python.is_reserved(&identifier) {
+            identifier.push('_');
+        }
+        identifier
+    }
+
+    fn field_type_to_python(&self, field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::String => "str".to_string(),
+            FieldType::Integer(_) => "int".to_string(),
+            FieldType::Float(_) => "float".to_string(),
+            FieldType::Boolean => "bool".to_string(),
+            FieldType::ID | FieldType::EntityID(_) => "HelixID".to_string(),
+            FieldType::Date => "datetime".to_string(),
+            FieldType::Vector(_) => "Vector".to_string(),
+            FieldType::Array(inner) => {
+                format!("List[{}]", self.field_type_to_python(inner))
+            }
+            FieldType::Optional(inner) => {
+                format!("Optional[{}]", self.field_type_to_python(inner))
+            }
+            FieldType::Custom(name) => self
+                .options
+                .type_mappings
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| "Any".to_string()),
+        }
+    }
+
+    fn return_type_to_python(&self, return_type: &ReturnType) -> String {
+        match return_type {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                Language::Python.type_name(name)
+            }
+            ReturnType::Scalar(field_type) => self.field_type_to_python(field_type),
+            ReturnType::Array(inner) => {
+                format!("List[{}]", self.return_type_to_python(inner))
+            }
+            ReturnType::Object(_) => "Dict[str, Any]".to_string(),
+            ReturnType::Unknown => "Any".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = "
+        N::User { name: String, class: String, Age: U8 DEFAULT 0, Nick: String?, Tags: [String] }
+        E::Follows { From: User, To: User, Properties: { Since: Date } }
+        QUERY getUser(id: ID, Nick: String?) =>
+            u <- N<User>(id)
+            RETURN u
+    ";
+
+    fn emit(options: PythonOptions) -> String {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), SCHEMA.to_string())])
+                .unwrap();
+        let emitter = PythonEmitter {
+            schema: &schema,
+            options: &options,
+        };
+        emitter.check_names().unwrap();
+        emitter.generate()
+    }
+
+    #[test]
+    fn models_declare_properties() {
+        let output = emit(PythonOptions::default());
+        assert!(output.contains(
+            "class User(BaseNode):\n\
+             \x20   name: str\n\
+             \x20   class_: str = Field(alias=\"class\")\n\
+             \x20   Age: int\n\
+             \x20   Nick: Optional[str] = None\n\
+             \x20   Tags: List[str]\n"
+        ));
+        assert!(output.contains(
+            "class Follows(BaseEdge):\n\
+             \x20   from_: User = Field(alias=\"from\")\n\
+             \x20   to: User\n\
+             \x20   Since: datetime\n"
+        ));
+    }
+
+    #[test]
+    fn params_and_results_are_models() {
+        let output = emit(PythonOptions::default());
+        assert!(output.contains(
+            "class GetUserParams(HelixModel):\n\
+             \x20   id: HelixID\n\
+             \x20   Nick: Optional[str] = None\n"
+        ));
+        assert!(output.contains("class GetUserResult(HelixModel):\n    u: User\n"));
+        assert!(output.contains("    GET_USER = \"getUser\"\n"));
+        assert!(
+            output.contains("    def get_user(self, params: GetUserParams) -> GetUserResult:\n")
+        );
+    }
+
+    #[test]
+    fn omitted_params_are_left_out() {
+        let output = emit(PythonOptions::default());
+        assert!(
+            output
+                .contains("        response = self.client.query(\"getUser\", _payload(params))\n")
+        );
+        assert!(output.contains(
+            "    return params.model_dump(mode=\"json\", by_alias=True, exclude_unset=True)\n"
+        ));
+    }
+
+    #[test]
+    fn renamed_fields_keep_schema_names_as_aliases() {
+        let output = emit(PythonOptions {
+            property_naming: NamingStrategy::Snake,
+            ..PythonOptions::default()
+        });
+        assert!(output.contains("    age: int = Field(alias=\"Age\")\n"));
+        assert!(output.contains("    nick: Optional[str] = Field(default=None, alias=\"Nick\")\n"));
+    }
+
+    #[test]
+    fn custom_types_use_mappings() {
+        let schema = HelixQLSchemaParser::parse_sources(&[(
+            PathBuf::from("schema.hx"),
+            "N::Place { name: String, at: Point, area: Polygon }".to_string(),
+        )])
+        .unwrap();
+        let options = PythonOptions {
+            type_mappings: IndexMap::from([(
+                "Point".to_string(),
+                "tuple[float, float]".to_string(),
+            )]),
+            ..PythonOptions::default()
+        };
+        let output = PythonEmitter {
+            schema: &schema,
+            options: &options,
+        }
+        .generate();
+        assert!(output.contains("    at: tuple[float, float]\n    area: Any\n"));
+    }
+
+    #[test]
+    fn reserved_names_are_escaped() {
+        assert_eq!(PythonEmitter::identifier("from"), "from_");
+        assert_eq!(PythonEmitter::identifier("str"), "str_");
+        assert_eq!(PythonEmitter::identifier("first name"), "first_name");
+        assert_eq!(PythonEmitter::identifier("2nd"), "_2nd");
+    }
+}
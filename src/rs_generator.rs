@@ -0,0 +1,521 @@
+use crate::{
+    error::Result,
+    generator::{Generator, GeneratorOptions, OutputFile},
+    identifier::{Language, Namespace, to_pascal_case, to_snake_case},
+    schema::*,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// Settings for the `rust` target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RustOptions {
+    /// Rust types for HelixQL types this tool does not know, by name.
+    pub type_mappings: IndexMap<String, String>,
+}
+
+/// Emits serde structs and an async reqwest-based client trait.
+pub struct RustGenerator {
+    options: RustOptions,
+}
+
+impl RustGenerator {
+    pub fn new(options: RustOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Generator for RustGenerator {
+    fn generate(
+        &self,
+        schema: &HelixSchema,
+        options: &GeneratorOptions,
+    ) -> Result<Vec<OutputFile>> {
+        let emitter = RustEmitter {
+            schema,
+            options: &self.options,
+        };
+        emitter.check_names()?;
+        Ok(vec![OutputFile {
+            path: options.output.clone(),
+            contents: emitter.generate(),
+        }])
+    }
+}
+
+struct RustEmitter<'a> {
+    schema: &'a HelixSchema,
+    options: &'a RustOptions,
+}
+
+impl RustEmitter<'_> {
+    /// Rejects schemas where distinct names would generate the same struct,
+    /// method or field.
+    fn check_names(&self) -> Result<()> {
+        let mut structs = Namespace::new("Rust type names");
+        let entities = (self.schema.nodes.keys().map(|name| ("node", name)))
+            .chain(self.schema.vectors.keys().map(|name| ("vector", name)))
+            .chain(self.schema.edges.keys().map(|name| ("edge", name)));
+        for (kind, name) in entities {
+            structs.claim(&format!("{kind} `{name}`"), Language::Rust.type_name(name))?;
+        }
+        let mut methods = Namespace::new("TypedHelixDBClient");
+        for name in self.schema.queries.keys() {
+            let source = format!("query `{name}`");
+            structs.claim(&source, format!("{}Params", to_pascal_case(name)))?;
+            structs.claim(&source, format!("{}Result", to_pascal_case(name)))?;
+            methods.claim(&source, Self::identifier(name))?;
+        }
+
+        for (name, node) in &self.schema.nodes {
+            Self::check_fields(
+                name,
+                &["id", "created_at", "updated_at"],
+                "property",
+                node.properties.keys(),
+            )?;
+        }
+        for (name, vector) in &self.schema.vectors {
+            Self::check_fields(name, &["id"], "property", vector.properties.keys())?;
+        }
+        for (name, edge) in &self.schema.edges {
+            Self::check_fields(
+                name,
+                &["id", "created_at", "from", "to"],
+                "property",
+                edge.properties.keys(),
+            )?;
+        }
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            Self::check_fields(
+                &format!("{pascal}Params"),
+                &[],
+                "parameter",
+                query.parameters.keys(),
+            )?;
+            let returns = query.returns.iter().map(|field| &field.name);
+            Self::check_fields(&format!("{pascal}Result"), &[], "field", returns)?;
+        }
+        Ok(())
+    }
+
+    /// `base` are built-in fields, which a property of the same name
+    /// replaces.
+    fn check_fields<'n>(
+        owner: &str,
+        base: &[&str],
+        kind: &str,
+        fields: impl Iterator<Item = &'n String>,
+    ) -> Result<()> {
+        let fields: Vec<&String> = fields.collect();
+        let mut namespace = Namespace::new(owner);
+        for field in base
+            .iter()
+            .filter(|field| !fields.iter().any(|f| f == *field))
+        {
+            namespace.claim(
+                &format!("built-in field `{field}`"),
+                Self::identifier(field),
+            )?;
+        }
+        for field in fields {
+            namespace.claim(&format!("{kind} `{field}`"), Self::identifier(field))?;
+        }
+        Ok(())
+    }
+
+    fn generate(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("// Generated Rust types for HelixDB schema\n");
+        output.push_str("// This file is auto-generated. Do not edit manually.\n");
+        output.push_str("// Generated with helix-ts-gen\n\n");
+
+        output.push_str("#![allow(dead_code)]\n\n");
+        output.push_str("use serde::de::DeserializeOwned;\n");
+        output.push_str("use serde::{Deserialize, Serialize};\n");
+        output.push_str("use std::future::Future;\n\n");
+
+        output.push_str("// Utility types\n");
+        output.push_str("/// HelixDB IDs are UUIDs, sent as strings.\n");
+        output.push_str("pub type HelixID = String;\n");
+        output.push_str("/// RFC 3339 timestamps, as HelixDB sends them.\n");
+        output.push_str("pub type Timestamp = String;\n\n");
+
+        output.push_str(&self.generate_node_types());
+        output.push_str(&self.generate_vector_types());
+        output.push_str(&self.generate_edge_types());
+        output.push_str(&self.generate_query_types());
+        output.push_str(&self.generate_client());
+
+        output
+    }
+
+    fn generate_node_types(&self) -> String {
+        let mut output = String::from("// Node types\n");
+        for (name, node) in &self.schema.nodes {
+            let base = [
+                ("id", "HelixID".to_string()),
+                ("created_at", "Option<Timestamp>".to_string()),
+                ("updated_at", "Option<Timestamp>".to_string()),
+            ];
+            output.push_str(&self.entity_struct(name, &base, &node.properties));
+        }
+        output
+    }
+
+    fn generate_vector_types(&self) -> String {
+        let mut output = String::from("// Vector types\n");
+        for (name, vector) in &self.schema.vectors {
+            let base = [("id", "HelixID".to_string())];
+            output.push_str(&self.entity_struct(name, &base, &vector.properties));
+        }
+        output
+    }
+
+    fn generate_edge_types(&self) -> String {
+        let mut output = String::from("// Edge types\n");
+        for (name, edge) in &self.schema.edges {
+            let base = [
+                ("id", "Option<HelixID>".to_string()),
+                ("created_at", "Option<Timestamp>".to_string()),
+                ("from", Language::Rust.type_name(&edge.from_node)),
+                ("to", Language::Rust.type_name(&edge.to_node)),
+            ];
+            output.push_str(&self.entity_struct(name, &base, &edge.properties));
+        }
+        output
+    }
+
+    fn generate_query_types(&self) -> String {
+        let mut output = String::from("// Query parameter and result types\n");
+
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            output.push_str(&self.entity_struct(
+                &format!("{pascal}Params"),
+                &[],
+                &query.parameters,
+            ));
+
+            let result_fields: Vec<(String, String)> = query
+                .returns
+                .iter()
+                .map(|field| {
+                    (
+                        field.name.clone(),
+                        self.return_type_to_rust(&field.return_type),
+                    )
+                })
+                .collect();
+            output.push_str(&Self::rust_struct(
+                &format!("{pascal}Result"),
+                &result_fields,
+            ));
+        }
+
+        output
+    }
+
+    fn generate_client(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("// Client trait for type-safe HelixDB operations\n");
+        output.push_str("pub trait TypedHelixDBClient {\n");
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            if let Some(ref description) = query.description {
+                output.push_str(&format!("    /// {description}\n"));
+            }
+            output.push_str(&format!(
+                "    fn {}(\n        &self,\n        params: &{pascal}Params,\n    ) -> impl Future<Output = Result<{pascal}Result, reqwest::Error>> + Send;\n\n",
+                Self::identifier(name)
+            ));
+        }
+        trim_trailing_blank_line(&mut output);
+        output.push_str("}\n\n");
+
+        output.push_str(
+            r#"/// Minimal reqwest-based HelixDB client: each query is a POST to `/{QueryName}`.
+#[derive(Debug, Clone)]
+pub struct HelixDBClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl HelixDBClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    pub fn with_client(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+        }
+    }
+
+    pub async fn query<P: Serialize + ?Sized, R: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: &P,
+    ) -> Result<R, reqwest::Error> {
+        self.client
+            .post(format!("{}/{}", self.base_url.trim_end_matches('/'), endpoint))
+            .json(params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
+
+"#,
+        );
+
+        output.push_str("impl TypedHelixDBClient for HelixDBClient {\n");
+        for name in self.schema.queries.keys() {
+            let pascal = to_pascal_case(name);
+            output.push_str(&format!(
+                "    async fn {}(\n        &self,\n        params: &{pascal}Params,\n    ) -> Result<{pascal}Result, reqwest::Error> {{\n        self.query(\"{name}\", params).await\n    }}\n\n",
+                Self::identifier(name)
+            ));
+        }
+        trim_trailing_blank_line(&mut output);
+        output.push_str("}\n");
+
+        output
+    }
+
+    /// A struct whose `base` fields (already mapped to Rust types) are
+    /// overridden by declared properties of the same name.
+    fn entity_struct(
+        &self,
+        name: &str,
+        base: &[(&str, String)],
+        properties: &IndexMap<String, FieldType>,
+    ) -> String {
+        let fields: Vec<(String, String)> = base
+            .iter()
+            .filter(|(field_name, _)| !properties.contains_key(*field_name))
+            .map(|(field_name, rust_type)| (field_name.to_string(), rust_type.clone()))
+            .chain(properties.iter().map(|(prop_name, field_type)| {
+                (prop_name.clone(), self.field_type_to_rust(field_type))
+            }))
+            .collect();
+        Self::rust_struct(name, &fields)
+    }
+
+    fn rust_struct(name: &str, fields: &[(String, String)]) -> String {
+        let mut output = String::new();
+        output.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        output.push_str(&format!(
+            "pub struct {} {{\n",
+            Language::Rust.type_name(name)
+        ));
+
+        for (field_name, rust_type) in fields {
+            let identifier = Self::identifier(field_name);
+            let raw_name = identifier.trim_start_matches("r#");
+            if raw_name != field_name {
+                output.push_str(&format!("    #[serde(rename = \"{field_name}\")]\n"));
+            }
+            if rust_type.starts_with("Option<") {
+                output
+                    .push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            output.push_str(&format!("    pub {identifier}: {rust_type},\n"));
+        }
+
+        output.push_str("}\n\n");
+        output
+    }
+
+    /// Makes a name usable as a Rust field or method, e.g. `type` -> `r#type`.
+    fn identifier(name: &str) -> String {
+        let mut identifier: String = to_snake_case(name)
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        if identifier.is_empty() || identifier.starts_with(|c: char| c.is_ascii_digit()) {
+            identifier.insert(0, '_');
+        }
+        match identifier.as_str() {
+            "self" | "Self" | "super" | "crate" => format!("{identifier}_"),
+            keyword if Language::Rust.is_keyword(keyword) => format!("r#{identifier}"),
+            _ => identifier,
+        }
+    }
+
+    fn field_type_to_rust(&self, field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::String => "String".to_string(),
+            FieldType::Integer(integer_type) => format!("{integer_type:?}").to_lowercase(),
+            FieldType::Float(FloatType::F32) => "f32".to_string(),
+            FieldType::Float(FloatType::F64) => "f64".to_string(),
+            FieldType::Boolean => "bool".to_string(),
+            FieldType::ID | FieldType::EntityID(_) => "HelixID".to_string(),
+            FieldType::Date => "Timestamp".to_string(),
+            FieldType::Vector(_) => "Vec<f64>".to_string(),
+            FieldType::Array(inner) => {
+                format!("Vec<{}>", self.field_type_to_rust(inner))
+            }
+            FieldType::Optional(inner) => {
+                format!("Option<{}>", self.field_type_to_rust(inner))
+            }
+            FieldType::Custom(name) => self
+                .options
+                .type_mappings
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| "serde_json::Value".to_string()),
+        }
+    }
+
+    fn return_type_to_rust(&self, return_type: &ReturnType) -> String {
+        match return_type {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                Language::Rust.type_name(name)
+            }
+            ReturnType::Scalar(field_type) => self.field_type_to_rust(field_type),
+            ReturnType::Array(inner) => {
+                format!("Vec<{}>", self.return_type_to_rust(inner))
+            }
+            ReturnType::Object(_) | ReturnType::Unknown => "serde_json::Value".to_string(),
+        }
+    }
+}
+
+fn trim_trailing_blank_line(output: &mut String) {
+    if output.ends_with("\n\n") {
+        output.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = "
+        N::User { name: String, type: String, Score: F64, Tags: [String], Nick: String?, self: I32 }
+        V::Doc { content: String }
+        E::Follows { From: User, To: User, Properties: { Since: Date } }
+        QUERY getUser(id: ID, Nick: String?) =>
+            u <- N<User>(id)
+            RETURN u
+    ";
+
+    fn parse(source: &str) -> HelixSchema {
+        HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+            .unwrap()
+    }
+
+    fn emit(options: RustOptions) -> String {
+        let schema = parse(SCHEMA);
+        let emitter = RustEmitter {
+            schema: &schema,
+            options: &options,
+        };
+        emitter.check_names().unwrap();
+        emitter.generate()
+    }
+
+    #[test]
+    fn structs_declare_built_in_fields_and_properties() {
+        let output = emit(RustOptions::default());
+        assert!(output.contains(
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n\
+             pub struct User {\n\
+             \x20   pub id: HelixID,\n\
+             \x20   #[serde(default, skip_serializing_if = \"Option::is_none\")]\n\
+             \x20   pub created_at: Option<Timestamp>,\n\
+             \x20   #[serde(default, skip_serializing_if = \"Option::is_none\")]\n\
+             \x20   pub updated_at: Option<Timestamp>,\n\
+             \x20   pub name: String,\n"
+        ));
+        assert!(
+            output
+                .contains("pub struct Doc {\n    pub id: HelixID,\n    pub content: String,\n}\n")
+        );
+        assert!(output.contains(
+            "    pub from: User,\n\
+             \x20   pub to: User,\n\
+             \x20   #[serde(rename = \"Since\")]\n\
+             \x20   pub since: Timestamp,\n"
+        ));
+    }
+
+    #[test]
+    fn queries_get_params_results_and_client_methods() {
+        let output = emit(RustOptions::default());
+        assert!(output.contains(
+            "pub struct GetUserParams {\n\
+             \x20   pub id: HelixID,\n\
+             \x20   #[serde(rename = \"Nick\")]\n\
+             \x20   #[serde(default, skip_serializing_if = \"Option::is_none\")]\n\
+             \x20   pub nick: Option<String>,\n\
+             }\n"
+        ));
+        assert!(output.contains("pub struct GetUserResult {\n    pub u: User,\n}\n"));
+        assert!(output.contains(
+            "    fn get_user(\n\
+             \x20       &self,\n\
+             \x20       params: &GetUserParams,\n\
+             \x20   ) -> impl Future<Output = Result<GetUserResult, reqwest::Error>> + Send;\n"
+        ));
+        assert!(output.contains("        self.query(\"getUser\", params).await\n"));
+    }
+
+    #[test]
+    fn renamed_fields_keep_schema_names() {
+        let output = emit(RustOptions::default());
+        assert!(output.contains("    #[serde(rename = \"Score\")]\n    pub score: f64,\n"));
+        assert!(output.contains("    #[serde(rename = \"Tags\")]\n    pub tags: Vec<String>,\n"));
+        assert!(output.contains("    #[serde(rename = \"self\")]\n    pub self_: i32,\n"));
+        // Raw identifiers keep the schema name, so they need no rename
+        assert!(output.contains("    pub name: String,\n    pub r#type: String,\n"));
+    }
+
+    #[test]
+    fn keywords_are_escaped() {
+        assert_eq!(RustEmitter::identifier("type"), "r#type");
+        assert_eq!(RustEmitter::identifier("match"), "r#match");
+        assert_eq!(RustEmitter::identifier("self"), "self_");
+        assert_eq!(RustEmitter::identifier("getHTTPStatus"), "get_http_status");
+        assert_eq!(RustEmitter::identifier("2nd"), "_2nd");
+    }
+
+    #[test]
+    fn custom_types_use_mappings() {
+        let schema = parse("N::Place { name: String, at: Point, area: Polygon }");
+        let options = RustOptions {
+            type_mappings: IndexMap::from([("Point".to_string(), "(f64, f64)".to_string())]),
+        };
+        let output = RustEmitter {
+            schema: &schema,
+            options: &options,
+        }
+        .generate();
+        assert!(output.contains("    pub at: (f64, f64),\n    pub area: serde_json::Value,\n"));
+    }
+
+    #[test]
+    fn colliding_fields_are_rejected() {
+        let schema = parse("N::User { CreatedAt: Date, name: String }");
+        let error = RustEmitter {
+            schema: &schema,
+            options: &RustOptions::default(),
+        }
+        .check_names()
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "built-in field `created_at` and property `CreatedAt` both generate `created_at` in User"
+        );
+    }
+}
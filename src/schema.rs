@@ -1,55 +1,321 @@
+use crate::error::{Error, Result};
+use crate::identifier::is_identifier;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Collections keep declaration order when the source has one (HelixQL
+/// files); introspected schemas are sorted alphabetically so that generated
+/// output is reproducible.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct HelixSchema {
-    pub nodes: HashMap<String, NodeDefinition>,
-    pub vectors: HashMap<String, VectorDefinition>,
-    pub queries: HashMap<String, QueryDefinition>,
-    pub edges: HashMap<String, EdgeDefinition>,
+    pub nodes: IndexMap<String, NodeDefinition>,
+    pub vectors: IndexMap<String, VectorDefinition>,
+    pub queries: IndexMap<String, QueryDefinition>,
+    pub edges: IndexMap<String, EdgeDefinition>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl HelixSchema {
+    /// Sorts every collection, including properties and parameters, by name.
+    pub fn sort(&mut self) {
+        self.nodes.sort_keys();
+        self.vectors.sort_keys();
+        self.queries.sort_keys();
+        self.edges.sort_keys();
+
+        for node in self.nodes.values_mut() {
+            node.properties.sort_keys();
+            node.defaults.sort_keys();
+        }
+        for vector in self.vectors.values_mut() {
+            vector.properties.sort_keys();
+            vector.defaults.sort_keys();
+        }
+        for edge in self.edges.values_mut() {
+            edge.properties.sort_keys();
+            edge.defaults.sort_keys();
+        }
+        for query in self.queries.values_mut() {
+            query.parameters.sort_keys();
+        }
+    }
+
+    /// Checks that element names are usable as identifiers and that edges
+    /// connect declared nodes or vectors.
+    pub fn validate(&self) -> Result<()> {
+        let names = [
+            ("node", self.nodes.keys().collect::<Vec<_>>()),
+            ("vector", self.vectors.keys().collect()),
+            ("edge", self.edges.keys().collect()),
+            ("query", self.queries.keys().collect()),
+        ];
+        for (kind, names) in names {
+            for name in names {
+                if !is_identifier(name) {
+                    return Err(Error::InvalidIdentifier {
+                        name: name.clone(),
+                        context: format!("{kind} name"),
+                    });
+                }
+            }
+        }
+
+        for edge in self.edges.values() {
+            for (end, target) in [("From", &edge.from_node), ("To", &edge.to_node)] {
+                if !self.nodes.contains_key(target) && !self.vectors.contains_key(target) {
+                    return Err(Error::UnknownType {
+                        name: target.clone(),
+                        context: format!("`{end}` of edge `{}`", edge.name),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NodeDefinition {
     pub name: String,
-    pub properties: HashMap<String, FieldType>,
+    pub properties: IndexMap<String, FieldType>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub defaults: IndexMap<String, DefaultValue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VectorDefinition {
     pub name: String,
-    pub properties: HashMap<String, FieldType>,
+    pub properties: IndexMap<String, FieldType>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub defaults: IndexMap<String, DefaultValue>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EdgeDefinition {
     pub name: String,
     pub from_node: String,
     pub to_node: String,
-    pub properties: HashMap<String, FieldType>,
+    pub properties: IndexMap<String, FieldType>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub defaults: IndexMap<String, DefaultValue>,
+}
+
+/// A property's `DEFAULT`, filled in by HelixDB when the property is not
+/// given on creation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DefaultValue {
+    /// `DEFAULT NOW`: the time of creation.
+    Now,
+    /// A literal in HelixQL syntax, e.g. `0`, `"guest"` or `true`.
+    Literal(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl fmt::Display for DefaultValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefaultValue::Now => write!(f, "NOW"),
+            DefaultValue::Literal(literal) => write!(f, "{literal}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QueryDefinition {
     pub name: String,
-    pub parameters: HashMap<String, FieldType>,
-    pub returns: String,
+    pub parameters: IndexMap<String, FieldType>,
+    pub returns: Vec<ReturnField>,
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A single named value in a query's RETURN clause.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReturnField {
+    pub name: String,
+    pub return_type: ReturnType,
+}
+
+/// The inferred type of a returned value. `Unknown` is used when the query
+/// body is unavailable (plain introspection) or cannot be type-checked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReturnType {
+    Node(String),
+    Vector(String),
+    Edge(String),
+    Scalar(FieldType),
+    Array(Box<ReturnType>),
+    Object(Vec<ReturnField>),
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FieldType {
     String,
-    Integer,
-    Float,
+    Integer(IntegerType),
+    Float(FloatType),
     Boolean,
+    /// A HelixDB ID (a UUID, sent as a string).
     ID,
+    /// An ID known to identify a particular node, vector or edge type.
+    EntityID(String),
+    /// A point in time, sent as an RFC 3339 string.
+    Date,
     Vector(usize),
     Array(Box<FieldType>),
     Optional(Box<FieldType>),
     Custom(String),
 }
 
+/// HelixQL integer types, kept exact so that each backend can pick a
+/// representation that does not lose precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntegerType {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+impl IntegerType {
+    pub fn bits(self) -> u32 {
+        match self {
+            IntegerType::I8 | IntegerType::U8 => 8,
+            IntegerType::I16 | IntegerType::U16 => 16,
+            IntegerType::I32 | IntegerType::U32 => 32,
+            IntegerType::I64 | IntegerType::U64 => 64,
+            IntegerType::I128 | IntegerType::U128 => 128,
+        }
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntegerType::I8
+                | IntegerType::I16
+                | IntegerType::I32
+                | IntegerType::I64
+                | IntegerType::I128
+        )
+    }
+
+    /// Whether every value fits in an IEEE 754 double without losing
+    /// precision (i.e. a JavaScript `number`).
+    pub fn fits_in_f64(self) -> bool {
+        self.bits() <= 32
+    }
+
+    /// Inclusive bounds, for integers narrow enough to express them as `i64`.
+    pub fn range(self) -> Option<(i64, i64)> {
+        match self {
+            IntegerType::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            IntegerType::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            IntegerType::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            IntegerType::U8 => Some((0, u8::MAX.into())),
+            IntegerType::U16 => Some((0, u16::MAX.into())),
+            IntegerType::U32 => Some((0, u32::MAX.into())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FloatType {
+    F32,
+    F64,
+}
+
+impl FieldType {
+    pub fn parse(type_str: &str) -> Result<FieldType> {
+        match type_str {
+            "String" => Ok(FieldType::String),
+            "I8" => Ok(FieldType::Integer(IntegerType::I8)),
+            "I16" => Ok(FieldType::Integer(IntegerType::I16)),
+            "I32" => Ok(FieldType::Integer(IntegerType::I32)),
+            "I64" => Ok(FieldType::Integer(IntegerType::I64)),
+            "I128" => Ok(FieldType::Integer(IntegerType::I128)),
+            "U8" => Ok(FieldType::Integer(IntegerType::U8)),
+            "U16" => Ok(FieldType::Integer(IntegerType::U16)),
+            "U32" => Ok(FieldType::Integer(IntegerType::U32)),
+            "U64" => Ok(FieldType::Integer(IntegerType::U64)),
+            "U128" => Ok(FieldType::Integer(IntegerType::U128)),
+            "F32" => Ok(FieldType::Float(FloatType::F32)),
+            "F64" => Ok(FieldType::Float(FloatType::F64)),
+            "Boolean" => Ok(FieldType::Boolean),
+            "ID" => Ok(FieldType::ID),
+            "Date" => Ok(FieldType::Date),
+            s if s.ends_with('?') => {
+                let inner = Self::parse(&s[..s.len() - 1])?;
+                Ok(FieldType::Optional(Box::new(inner)))
+            }
+            s if s.starts_with("Option<") && s.ends_with(">") => {
+                let inner = Self::parse(&s[7..s.len() - 1])?;
+                Ok(FieldType::Optional(Box::new(inner)))
+            }
+            s if s.starts_with("Array(") && s.ends_with(")") => {
+                let inner_type = &s[6..s.len() - 1];
+                let inner = Self::parse(inner_type)?;
+                Ok(FieldType::Array(Box::new(inner)))
+            }
+            s if s.starts_with("Vector<") && s.ends_with(">") => {
+                let dim_str = &s[7..s.len() - 1];
+                let dim = dim_str.parse::<usize>().map_err(|e| Error::InvalidType {
+                    type_name: type_str.to_string(),
+                    message: format!("vector dimension `{dim_str}` is not a number ({e})"),
+                })?;
+                Ok(FieldType::Vector(dim))
+            }
+            _ => Ok(FieldType::Custom(type_str.to_string())),
+        }
+    }
+}
+
+/// HelixQL spelling of the type, e.g. `[I32]` or `Vector<1536>`. Entity IDs
+/// print as plain `ID`, as they do on the wire.
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::String => write!(f, "String"),
+            FieldType::Integer(integer_type) => write!(f, "{integer_type:?}"),
+            FieldType::Float(float_type) => write!(f, "{float_type:?}"),
+            FieldType::Boolean => write!(f, "Boolean"),
+            FieldType::ID | FieldType::EntityID(_) => write!(f, "ID"),
+            FieldType::Date => write!(f, "Date"),
+            FieldType::Vector(dim) => write!(f, "Vector<{dim}>"),
+            FieldType::Array(inner) => write!(f, "[{inner}]"),
+            FieldType::Optional(inner) => write!(f, "{inner}?"),
+            FieldType::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl fmt::Display for ReturnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                write!(f, "{name}")
+            }
+            ReturnType::Scalar(field_type) => write!(f, "{field_type}"),
+            ReturnType::Array(inner) => write!(f, "[{inner}]"),
+            ReturnType::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, field) in fields.iter().enumerate() {
+                    let separator = if i == 0 { "" } else { ", " };
+                    write!(f, "{separator}{}: {}", field.name, field.return_type)?;
+                }
+                write!(f, "}}")
+            }
+            ReturnType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
 // API response structures
 #[derive(Debug, Deserialize)]
 pub struct IntrospectionResponse {
@@ -67,13 +333,13 @@ pub struct SchemaData {
 #[derive(Debug, Deserialize)]
 pub struct NodeInfo {
     pub name: String,
-    pub properties: HashMap<String, String>,
+    pub properties: HashMap<String, PropertyInfo>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct VectorInfo {
     pub name: String,
-    pub properties: HashMap<String, String>,
+    pub properties: HashMap<String, PropertyInfo>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -81,12 +347,32 @@ pub struct EdgeInfo {
     pub name: String,
     pub from: String,
     pub to: String,
-    pub properties: HashMap<String, String>,
+    pub properties: HashMap<String, PropertyInfo>,
+}
+
+/// A property as a type string (`"String"`, `"I32?"`), or as an object
+/// spelling out whether it is optional and its default.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PropertyInfo {
+    Type(String),
+    Detailed {
+        #[serde(rename = "type")]
+        type_name: String,
+        #[serde(default)]
+        optional: bool,
+        /// `"NOW"`, or a JSON literal.
+        #[serde(default)]
+        default: Option<serde_json::Value>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
 pub struct QueryInfo {
     pub name: String,
-    pub parameters: HashMap<String, String>,
+    pub parameters: HashMap<String, PropertyInfo>,
     pub returns: Vec<String>,
+    /// HelixQL source of the query body, when the server exposes it.
+    #[serde(default)]
+    pub body: Option<String>,
 }
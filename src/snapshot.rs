@@ -0,0 +1,101 @@
+use crate::error::{Error, Result};
+use crate::schema::HelixSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Bumped whenever the serialized form of [`HelixSchema`] changes
+/// incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A normalized schema saved to JSON, so clients can be regenerated without
+/// access to the database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub schema: HelixSchema,
+}
+
+impl Snapshot {
+    pub fn new(schema: HelixSchema) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            schema,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json =
+            serde_json::to_string_pretty(self).expect("schema serialization cannot fail");
+        json.push('\n');
+        json
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let snapshot: Snapshot =
+            serde_json::from_str(json).map_err(|e| Error::Snapshot(e.to_string()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::Snapshot(format!(
+                "unsupported version {} (expected {SNAPSHOT_VERSION})",
+                snapshot.version
+            )));
+        }
+        snapshot.schema.validate()?;
+        Ok(snapshot)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).map_err(Error::file(path))?;
+        Self::from_json(&json).map_err(|e| match e {
+            Error::Snapshot(message) => Error::Snapshot(format!("{}: {message}", path.display())),
+            e => e,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    fn schema() -> HelixSchema {
+        let source = "
+            N::User { name: String, Age: U8 DEFAULT 0, Nick: String? }
+            E::Follows { From: User, To: User, Properties: { Since: Date } }
+            QUERY getUser(id: ID) =>
+                u <- N<User>(id)
+                RETURN u
+        ";
+        HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+            .unwrap()
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.json");
+        let snapshot = Snapshot::new(schema());
+        fs::write(&path, snapshot.to_json()).unwrap();
+        assert_eq!(Snapshot::load(&path).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.json");
+        let mut snapshot = Snapshot::new(schema());
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        fs::write(&path, snapshot.to_json()).unwrap();
+        let error = Snapshot::load(&path).unwrap_err();
+        assert!(matches!(error, Error::Snapshot(_)));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "invalid schema snapshot: {}: unsupported version {} (expected {SNAPSHOT_VERSION})",
+                path.display(),
+                SNAPSHOT_VERSION + 1
+            )
+        );
+    }
+}
@@ -1,26 +1,373 @@
 use crate::{
+    error::Result,
+    generator::{Generator, GeneratorOptions, OutputFile},
+    identifier::{Language, Namespace, NamingStrategy, to_pascal_case, to_screaming_snake_case},
     schema::*,
-    utils::{to_pascal_case, to_snake_case},
+    zod_generator::ZodGenerator,
 };
+use indexmap::IndexMap;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Settings for the `typescript` target.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeScriptOptions {
+    /// Also emit Zod schemas and let `createTypedClient` validate params.
+    pub zod: bool,
+    /// Representation of integers that do not fit in a JS `number`
+    /// (`I64`, `U64`, `I128`, `U128`).
+    pub large_integers: LargeIntegerPolicy,
+    /// Emit a distinct ID type per entity (`UserId`, `KnowsId`) so IDs of
+    /// different entities cannot be mixed up.
+    pub branded_ids: bool,
+    /// TypeScript types for HelixQL types this tool does not know, by name.
+    pub type_mappings: IndexMap<String, String>,
+    /// Runtime the generated client sends queries with.
+    pub client: ClientKind,
+    /// One file, or a directory of modules.
+    pub layout: Layout,
+    /// Also emit `assert{Name}` functions that throw on the first mismatched
+    /// field, next to the `is{Name}` type guards.
+    pub assertions: bool,
+    /// Spelling of properties and parameters in the generated types. When it
+    /// differs from the schema, the client converts requests and responses.
+    pub property_naming: NamingStrategy,
+    /// Type of `Date` properties and the `created_at`/`updated_at` fields.
+    pub dates: DateRepresentation,
+}
+
+/// Fields of `BaseNode`, `BaseVector` and `BaseEdge`, for runtime checks.
+fn base_fields(base: &str) -> Vec<(&'static str, FieldType)> {
+    let optional = |field_type| FieldType::Optional(Box::new(field_type));
+    let timestamp = || optional(FieldType::Date);
+    match base {
+        "BaseNode" => vec![
+            ("id", FieldType::ID),
+            ("created_at", timestamp()),
+            ("updated_at", timestamp()),
+        ],
+        "BaseEdge" => vec![("id", optional(FieldType::ID)), ("created_at", timestamp())],
+        _ => vec![("id", FieldType::ID)],
+    }
+}
+
+fn base_field_names(base: &str) -> Vec<&'static str> {
+    base_fields(base)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The type inside `Optional`, and whether there was one.
+fn split_optional(field_type: &FieldType) -> (&FieldType, bool) {
+    match field_type {
+        FieldType::Optional(inner) => (inner, true),
+        field_type => (field_type, false),
+    }
+}
+
+/// The integer type of `field_type` (or of its elements) if it is wider
+/// than a JS `number`.
+fn wide_integer(field_type: &FieldType) -> Option<IntegerType> {
+    match field_type {
+        FieldType::Integer(integer_type) if !integer_type.fits_in_f64() => Some(*integer_type),
+        FieldType::Optional(inner) | FieldType::Array(inner) => wide_integer(inner),
+        _ => None,
+    }
+}
+
+/// How the TypeScript output is split into files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    /// Everything in the output file.
+    #[default]
+    Single,
+    /// The output is a directory with `common.ts`, `nodes.ts`, `vectors.ts`,
+    /// `edges.ts`, `queries.ts`, `client.ts` and an `index.ts` barrel.
+    Split,
+    /// Like `Split`, with each node, vector and edge in its own file under
+    /// `nodes/`, `vectors/` and `edges/`.
+    PerEntity,
+}
+
+/// How the generated `createTypedClient` talks to HelixDB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClientKind {
+    /// Wrap a client from the `helix-ts` package.
+    #[default]
+    HelixTs,
+    /// Self-contained, using only `fetch`; no runtime dependencies.
+    Fetch,
+}
+
+/// How integers wider than a JS `number` can safely hold are typed. With
+/// `Bigint` and `String` the client parses responses and serializes params
+/// itself, so values keep every digit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LargeIntegerPolicy {
+    /// Plain `number`; values above 2^53 lose precision.
+    #[default]
+    Number,
+    Bigint,
+    /// Decimal strings.
+    String,
+}
+
+impl LargeIntegerPolicy {
+    pub fn typescript_type(self, integer_type: IntegerType) -> &'static str {
+        match self {
+            _ if integer_type.fits_in_f64() => "number",
+            LargeIntegerPolicy::Number => "number",
+            LargeIntegerPolicy::Bigint => "bigint",
+            LargeIntegerPolicy::String => "string",
+        }
+    }
+}
+
+/// How `Date` values, sent by HelixDB as RFC 3339 strings, are typed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateRepresentation {
+    /// The string as sent.
+    #[default]
+    IsoString,
+    /// `Date` objects; the client revives responses and serializes params.
+    Date,
+    /// Milliseconds since the Unix epoch, converted by the client.
+    Epoch,
+}
+
+impl DateRepresentation {
+    pub fn typescript_type(self) -> &'static str {
+        match self {
+            DateRepresentation::IsoString => "string",
+            DateRepresentation::Date => "Date",
+            DateRepresentation::Epoch => "number",
+        }
+    }
+}
 
 pub struct TypeScriptGenerator {
-    schema: HelixSchema,
+    options: TypeScriptOptions,
 }
 
 impl TypeScriptGenerator {
-    pub fn new(schema: HelixSchema) -> Self {
-        Self { schema }
+    pub fn new(options: TypeScriptOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl Generator for TypeScriptGenerator {
+    fn generate(
+        &self,
+        schema: &HelixSchema,
+        options: &GeneratorOptions,
+    ) -> Result<Vec<OutputFile>> {
+        let emitter = TypeScriptEmitter {
+            schema,
+            options: &self.options,
+        };
+        emitter.check_names()?;
+        let lossy = emitter.lossy_integer_fields();
+        if !lossy.is_empty() {
+            warn!(
+                "{} are typed as `number` and lose precision above 2^53; use --large-integers bigint or string to keep them exact",
+                lossy.join(", ")
+            );
+        }
+        if self.options.layout == Layout::Single {
+            return Ok(vec![OutputFile {
+                path: options.output.clone(),
+                contents: emitter.generate(),
+            }]);
+        }
+        Ok(emitter
+            .generate_modules()
+            .into_iter()
+            .map(|(module, contents)| OutputFile {
+                path: options.output.join(format!("{module}.ts")),
+                contents,
+            })
+            .collect())
+    }
+}
+
+struct TypeScriptEmitter<'a> {
+    schema: &'a HelixSchema,
+    options: &'a TypeScriptOptions,
+}
+
+impl TypeScriptEmitter<'_> {
+    /// Rejects schemas where distinct names would generate the same
+    /// declaration, endpoint constant or field.
+    fn check_names(&self) -> Result<()> {
+        let mut declarations = Namespace::new("TypeScript declarations");
+        if self.options.zod {
+            declarations.claim("the built-in `HelixIDSchema`", "HelixIDSchema".to_string())?;
+        }
+        let entities = (self.schema.nodes.keys().map(|name| ("node", name)))
+            .chain(self.schema.vectors.keys().map(|name| ("vector", name)))
+            .chain(self.schema.edges.keys().map(|name| ("edge", name)));
+        for (kind, name) in entities {
+            let source = format!("{kind} `{name}`");
+            for declaration in self.entity_declarations(name) {
+                declarations.claim(&source, declaration)?;
+            }
+        }
+        let mut endpoints = Namespace::new("QueryEndpoints");
+        for name in self.schema.queries.keys() {
+            let source = format!("query `{name}`");
+            let pascal = to_pascal_case(name);
+            declarations.claim(&source, format!("{pascal}Params"))?;
+            declarations.claim(&source, format!("{pascal}Result"))?;
+            if self.options.zod {
+                declarations.claim(&source, format!("{pascal}ParamsSchema"))?;
+            }
+            endpoints.claim(&source, to_screaming_snake_case(name))?;
+        }
+
+        for (name, node) in &self.schema.nodes {
+            self.check_fields(
+                name,
+                &base_field_names("BaseNode"),
+                "property",
+                node.properties.keys(),
+            )?;
+        }
+        for (name, vector) in &self.schema.vectors {
+            self.check_fields(
+                name,
+                &base_field_names("BaseVector"),
+                "property",
+                vector.properties.keys(),
+            )?;
+        }
+        for (name, edge) in &self.schema.edges {
+            self.check_fields(
+                name,
+                &[base_field_names("BaseEdge"), vec!["from", "to"]].concat(),
+                "property",
+                edge.properties.keys(),
+            )?;
+        }
+        for (name, query) in &self.schema.queries {
+            let pascal = to_pascal_case(name);
+            self.check_fields(
+                &format!("{pascal}Params"),
+                &[],
+                "parameter",
+                query.parameters.keys(),
+            )?;
+            let returns = query.returns.iter().map(|field| &field.name);
+            self.check_fields(&format!("{pascal}Result"), &[], "field", returns)?;
+        }
+        Ok(())
+    }
+
+    /// Every type, schema and function declared for the node, vector or
+    /// edge `name`.
+    fn entity_declarations(&self, name: &str) -> Vec<String> {
+        let mut declarations = vec![
+            Self::type_name(name),
+            format!("{name}Create"),
+            format!("{name}Update"),
+            format!("{name}Row"),
+            format!("validate{name}"),
+            format!("is{name}"),
+        ];
+        if self.options.branded_ids {
+            declarations.push(format!("{name}Id"));
+        }
+        if self.options.zod {
+            declarations.push(format!("{name}Schema"));
+            if self.options.branded_ids {
+                declarations.push(format!("{name}IdSchema"));
+            }
+        }
+        if self.options.assertions {
+            declarations.push(format!("assert{name}"));
+        }
+        if self.entity_conversion(name).is_some() {
+            declarations.push(format!("decode{name}"));
+        }
+        declarations
+    }
+
+    /// `base` are built-in fields, which a property of the same name
+    /// replaces.
+    fn check_fields<'n>(
+        &self,
+        owner: &str,
+        base: &[&str],
+        kind: &str,
+        fields: impl Iterator<Item = &'n String>,
+    ) -> Result<()> {
+        let fields: Vec<&String> = fields.collect();
+        let mut namespace = Namespace::new(owner);
+        for field in base
+            .iter()
+            .filter(|field| !fields.iter().any(|f| f == *field))
+        {
+            namespace.claim(
+                &format!("built-in field `{field}`"),
+                self.options.property_naming.apply(field),
+            )?;
+        }
+        for field in fields {
+            let generated = self.options.property_naming.apply(field);
+            namespace.claim(&format!("{kind} `{field}`"), generated)?;
+        }
+        Ok(())
     }
 
-    pub fn generate(&self) -> String {
+    /// `Entity.property (U64)` and `Query.param (U64)` for the properties and
+    /// parameters that the `number` policy types lossily.
+    fn lossy_integer_fields(&self) -> Vec<String> {
+        if self.options.large_integers != LargeIntegerPolicy::Number {
+            return Vec::new();
+        }
+        let properties = (self.schema.nodes.iter())
+            .map(|(name, node)| (name, &node.properties))
+            .chain((self.schema.vectors.iter()).map(|(name, vector)| (name, &vector.properties)))
+            .chain((self.schema.edges.iter()).map(|(name, edge)| (name, &edge.properties)))
+            .chain((self.schema.queries.iter()).map(|(name, query)| (name, &query.parameters)));
+        let mut fields = Vec::new();
+        for (owner, properties) in properties {
+            for (name, field_type) in properties {
+                if let Some(integer_type) = wide_integer(field_type) {
+                    fields.push(format!("{owner}.{name} ({integer_type:?})"));
+                }
+            }
+        }
+        fields
+    }
+
+    /// Whether the client parses and serializes JSON itself, to keep wide
+    /// integers exact.
+    fn exact_integers(&self) -> bool {
+        self.options.large_integers != LargeIntegerPolicy::Number
+    }
+
+    fn generate(&self) -> String {
         let mut output = String::new();
 
         output.push_str("// Generated TypeScript types for HelixDB schema\n");
         output.push_str("// This file is auto-generated. Do not edit manually.\n");
-        output.push_str("// Generated with helix-ts-gen from introspection endpoint\n\n");
+        output.push_str("// Generated with helix-ts-gen\n\n");
 
-        // Import HelixDB (only export available)
-        output.push_str("import HelixDB from 'helix-ts';\n\n");
+        if self.options.client == ClientKind::HelixTs {
+            // Import HelixDB (only export available)
+            output.push_str("import HelixDB from 'helix-ts';\n");
+        }
+        if self.options.zod {
+            output.push_str("import { z } from 'zod';\n");
+        }
+        output.push('\n');
 
         output.push_str(&self.generate_utility_types());
         output.push_str(&self.generate_helix_types()); // Add HelixDB types since they're not exported
@@ -28,142 +375,538 @@ impl TypeScriptGenerator {
         output.push_str(&self.generate_vector_types());
         output.push_str(&self.generate_edge_types());
         output.push_str(&self.generate_query_types());
+        if self.options.zod {
+            output.push_str(&self.generate_zod_schemas());
+        }
         output.push_str(&self.generate_client_interface());
         output.push_str(&self.generate_sdk_helpers());
+        output.push_str(&self.generate_type_guards());
 
         output
     }
 
-    fn generate_helix_types(&self) -> String {
-        r#"// HelixDB types (extracted from runtime since not exported)
-export type HelixDBClient = HelixDB;
-export type HelixDBResponse = Awaited<ReturnType<HelixDB['query']>>;
-export type HelixDBInput = Parameters<HelixDB['query']>[1];
+    /// The same declarations as [`Self::generate`], split into modules that
+    /// import from each other. Returns module paths (without extension) and
+    /// their contents, ending with an `index` barrel. In the per-entity
+    /// layout, `nodes`, `vectors` and `edges` re-export their entities.
+    fn generate_modules(&self) -> Vec<(String, String)> {
+        let per_entity = self.options.layout == Layout::PerEntity;
+        // Path, body and the names it takes from other modules
+        let mut modules: Vec<(String, String, HashSet<String>)> = Vec::new();
 
-"#
-        .to_string()
-    }
+        let mut common_uses = HashSet::new();
+        if self.options.client == ClientKind::HelixTs {
+            common_uses.insert("HelixDB".to_string());
+        }
+        modules.push((
+            "common".to_string(),
+            self.generate_utility_types() + &self.generate_helix_types(),
+            common_uses,
+        ));
 
-    fn generate_utility_types(&self) -> String {
-        r#"// Utility types
-export type Vector = number[];
-export type Optional<T> = T | null | undefined;
-export type HelixID = number;
+        let groups = [
+            (
+                "nodes",
+                "AnyNode",
+                self.schema.nodes.keys().collect::<Vec<_>>(),
+            ),
+            ("vectors", "AnyVector", self.schema.vectors.keys().collect()),
+            ("edges", "AnyEdge", self.schema.edges.keys().collect()),
+        ];
+        for (group, alias, names) in groups {
+            if per_entity {
+                let mut barrel = String::new();
+                for name in &names {
+                    let path = format!("{group}/{name}");
+                    barrel.push_str(&format!(
+                        "export * from '{}';\n",
+                        relative_specifier(group, &path)
+                    ));
+                    modules.push((path, self.entity_type(name), self.entity_uses(name)));
+                }
+                barrel.push('\n');
+                barrel.push_str(&Self::union_type(alias, names.iter().copied()));
+                let uses = names.iter().map(|name| Self::type_name(name)).collect();
+                modules.push((group.to_string(), barrel, uses));
+            } else {
+                let body = match group {
+                    "nodes" => self.generate_node_types(),
+                    "vectors" => self.generate_vector_types(),
+                    _ => self.generate_edge_types(),
+                };
+                let uses = names.iter().flat_map(|name| self.entity_uses(name));
+                modules.push((group.to_string(), body, uses.collect()));
+            }
+        }
 
-export interface BaseNode {
-  id: HelixID;
-  created_at?: number;
-  updated_at?: number;
-}
+        modules.push((
+            "queries".to_string(),
+            self.generate_query_types(),
+            self.query_type_uses(),
+        ));
+        if self.options.zod {
+            let mut uses = HashSet::from(["z".to_string()]);
+            if self.options.branded_ids {
+                uses.extend(self.entity_names().map(|name| format!("{name}Id")));
+            }
+            modules.push(("schemas".to_string(), self.generate_zod_schemas(), uses));
+        }
+        modules.push((
+            "client".to_string(),
+            self.generate_client_interface() + &self.generate_sdk_helpers(),
+            self.client_uses(),
+        ));
+        modules.push((
+            "guards".to_string(),
+            self.generate_type_guards(),
+            self.entity_names()
+                .map(|name| Self::type_name(name))
+                .collect(),
+        ));
 
-export interface BaseVector {
-  id: HelixID;
-}
+        let exports: Vec<Vec<(String, bool)>> = modules
+            .iter()
+            .map(|(_, body, _)| module_exports(body))
+            .collect();
 
-export interface BaseEdge {
-  id?: HelixID;
-  created_at?: number;
-}
+        let mut files: Vec<(String, String)> = modules
+            .iter()
+            .enumerate()
+            .map(|(i, (path, body, used))| {
+                let mut output = String::new();
+                output.push_str("// Generated TypeScript types for HelixDB schema\n");
+                output.push_str("// This file is auto-generated. Do not edit manually.\n");
+                output.push_str("// Generated with helix-ts-gen\n\n");
 
-"#
-        .to_string()
+                if used.contains("HelixDB") {
+                    output.push_str("import HelixDB from 'helix-ts';\n");
+                }
+                if used.contains("z") {
+                    output.push_str("import { z } from 'zod';\n");
+                }
+                for (j, ((other, _, _), other_exports)) in modules.iter().zip(&exports).enumerate()
+                {
+                    if i == j {
+                        continue;
+                    }
+                    let imported = |want_type: bool| -> Vec<&str> {
+                        other_exports
+                            .iter()
+                            .filter(|(name, is_type)| *is_type == want_type && used.contains(name))
+                            .map(|(name, _)| name.as_str())
+                            .collect()
+                    };
+                    let specifier = relative_specifier(path, other);
+                    let types = imported(true);
+                    if !types.is_empty() {
+                        output.push_str(&format!(
+                            "import type {{ {} }} from '{specifier}';\n",
+                            types.join(", ")
+                        ));
+                    }
+                    let values = imported(false);
+                    if !values.is_empty() {
+                        output.push_str(&format!(
+                            "import {{ {} }} from '{specifier}';\n",
+                            values.join(", ")
+                        ));
+                    }
+                }
+                output.push('\n');
+
+                output.push_str(body);
+                if !body.lines().any(|line| line.starts_with("export ")) {
+                    // Keep the file a module so the barrel can re-export it
+                    output.push_str("export {};\n");
+                }
+                (path.clone(), output)
+            })
+            .collect();
+
+        let mut index = String::from("// Generated with helix-ts-gen\n\n");
+        for (path, _, _) in modules.iter().filter(|(path, _, _)| !path.contains('/')) {
+            index.push_str(&format!("export * from './{path}';\n"));
+        }
+        files.push(("index".to_string(), index));
+
+        files
     }
 
-    fn generate_node_types(&self) -> String {
-        let mut output = String::new();
-        output.push_str("// Node types\n");
+    /// Names of all nodes, vectors and edges.
+    fn entity_names(&self) -> impl Iterator<Item = &String> {
+        (self.schema.nodes.keys())
+            .chain(self.schema.vectors.keys())
+            .chain(self.schema.edges.keys())
+    }
 
-        for (name, node) in &self.schema.nodes {
-            output.push_str(&format!("export interface {name} extends BaseNode {{\n"));
+    /// The declarations for the node, vector or edge `name`.
+    fn entity_type(&self, name: &str) -> String {
+        if let Some(node) = self.schema.nodes.get(name) {
+            self.node_type(name, node)
+        } else if let Some(vector) = self.schema.vectors.get(name) {
+            self.vector_type(name, vector)
+        } else {
+            self.edge_type(name, &self.schema.edges[name])
+        }
+    }
+
+    /// Names [`Self::entity_type`] refers to.
+    fn entity_uses(&self, name: &str) -> HashSet<String> {
+        let (base, properties, endpoints) = if let Some(node) = self.schema.nodes.get(name) {
+            ("BaseNode", &node.properties, Vec::new())
+        } else if let Some(vector) = self.schema.vectors.get(name) {
+            ("BaseVector", &vector.properties, Vec::new())
+        } else {
+            let edge = &self.schema.edges[name];
+            (
+                "BaseEdge",
+                &edge.properties,
+                vec![&edge.from_node, &edge.to_node],
+            )
+        };
+        let mut uses = HashSet::from([base.to_string()]);
+        if self.options.branded_ids {
+            uses.extend(["Brand".to_string(), "HelixID".to_string()]);
+        }
+        for field_type in properties.values() {
+            self.field_uses(split_optional(field_type).0, &mut uses);
+        }
+        uses.extend(endpoints.into_iter().map(|end| Self::type_name(end)));
+        uses
+    }
 
-            for (prop_name, field_type) in &node.properties {
-                let ts_type = Self::field_type_to_typescript(field_type);
-                output.push_str(&format!("  {prop_name}: {ts_type};\n"));
+    /// Names the query params and result types refer to.
+    fn query_type_uses(&self) -> HashSet<String> {
+        let mut uses = HashSet::new();
+        for query in self.schema.queries.values() {
+            let input_type = self.params_input_type(query);
+            for (param, field_type) in &query.parameters {
+                if !input_type
+                    .as_ref()
+                    .is_some_and(|(_, properties)| properties.contains_key(param))
+                {
+                    self.field_uses(split_optional(field_type).0, &mut uses);
+                }
+            }
+            if let Some((input_type, _)) = input_type {
+                uses.insert(input_type);
             }
+            for field in &query.returns {
+                self.return_uses(&field.return_type, &mut uses);
+            }
+        }
+        uses
+    }
 
-            output.push_str("}\n\n");
+    /// Names the client interface and SDK helpers refer to.
+    fn client_uses(&self) -> HashSet<String> {
+        let mut uses = HashSet::from(["HelixDBInput".to_string(), "HelixDBResponse".to_string()]);
+        if self.options.client == ClientKind::HelixTs && !self.exact_integers() {
+            uses.insert("HelixDB".to_string());
         }
+        for name in self.schema.queries.keys() {
+            let pascal = to_pascal_case(name);
+            uses.insert(format!("{pascal}Params"));
+            uses.insert(format!("{pascal}Result"));
+            if self.options.zod {
+                uses.insert(format!("{pascal}ParamsSchema"));
+            }
+        }
+        for name in self.entity_names() {
+            if self.entity_conversion(name).is_some() {
+                uses.insert(Self::type_name(name));
+            }
+        }
+        uses
+    }
 
-        if !self.schema.nodes.is_empty() {
-            let node_names: Vec<String> = self.schema.nodes.keys().cloned().collect();
-            output.push_str(&format!(
-                "export type AnyNode = {};\n\n",
-                node_names.join(" | ")
-            ));
+    /// Adds the names [`Self::field_type_to_typescript`] refers to.
+    fn field_uses(&self, field_type: &FieldType, uses: &mut HashSet<String>) {
+        match field_type {
+            FieldType::ID => {
+                uses.insert("HelixID".to_string());
+            }
+            FieldType::EntityID(name) if self.options.branded_ids => {
+                uses.insert(format!("{name}Id"));
+            }
+            FieldType::EntityID(_) => {
+                uses.insert("HelixID".to_string());
+            }
+            FieldType::Array(inner) => self.field_uses(inner, uses),
+            FieldType::Optional(inner) => {
+                uses.insert("Optional".to_string());
+                self.field_uses(inner, uses);
+            }
+            FieldType::Custom(name) if !self.options.type_mappings.contains_key(name) => {
+                uses.insert(name.clone());
+            }
+            _ => {}
         }
+    }
 
-        output
+    /// Adds the names [`Self::return_type_to_typescript`] refers to.
+    fn return_uses(&self, return_type: &ReturnType, uses: &mut HashSet<String>) {
+        match return_type {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                uses.insert(Self::type_name(name));
+            }
+            ReturnType::Scalar(field_type) => self.field_uses(field_type, uses),
+            ReturnType::Array(inner) => self.return_uses(inner, uses),
+            ReturnType::Object(fields) => {
+                for field in fields {
+                    self.return_uses(&field.return_type, uses);
+                }
+            }
+            ReturnType::Unknown => {}
+        }
     }
 
-    fn generate_vector_types(&self) -> String {
-        let mut output = String::new();
-        output.push_str("// Vector types\n");
+    fn generate_zod_schemas(&self) -> String {
+        ZodGenerator::new(self.schema)
+            .with_large_integers(self.options.large_integers)
+            .with_branded_ids(self.options.branded_ids)
+            .with_type_mappings(&self.options.type_mappings)
+            .with_property_naming(self.options.property_naming)
+            .with_dates(self.options.dates)
+            .generate()
+    }
 
-        for (name, vector) in &self.schema.vectors {
-            output.push_str(&format!(
-                "export interface {name} extends BaseVector {{\n"
-            ));
+    fn generate_helix_types(&self) -> String {
+        match self.options.client {
+            ClientKind::HelixTs => r#"// HelixDB types (extracted from runtime since not exported)
+export type HelixDBClient = HelixDB;
+export type HelixDBResponse = Awaited<ReturnType<HelixDB['query']>>;
+export type HelixDBInput = Parameters<HelixDB['query']>[1];
+
+"#
+            .to_string(),
+            ClientKind::Fetch => r#"// HelixDB wire types
+export type HelixDBResponse = Record<string, any>;
+export type HelixDBInput = object;
 
-            for (prop_name, field_type) in &vector.properties {
-                let ts_type = Self::field_type_to_typescript(field_type);
-                output.push_str(&format!("  {prop_name}: {ts_type};\n"));
+"#
+            .to_string(),
+        }
+    }
+
+    fn generate_utility_types(&self) -> String {
+        let mut output = String::from(
+            r#"// Utility types
+export type Vector = number[];
+export type Optional<T> = T | null | undefined;
+// HelixDB IDs are UUIDs
+export type HelixID = string;
+"#,
+        );
+        if self.options.branded_ids {
+            output.push_str(
+                "export type Brand<T, B extends string> = T & { readonly __brand: B };
+",
+            );
+        }
+        output.push('\n');
+        for base in ["BaseNode", "BaseVector", "BaseEdge"] {
+            output.push_str(&format!("\nexport interface {base} {{\n"));
+            for (name, field_type) in base_fields(base) {
+                output.push_str(&self.field(name, &field_type, None));
             }
+            output.push_str("}\n");
+        }
+        output.push('\n');
+        output
+    }
 
-            output.push_str("}\n\n");
+    /// `export type UserId = ...` and the matching `id` field, when branding.
+    fn branded_id(&self, name: &str, optional: bool) -> Option<(String, String)> {
+        if !self.options.branded_ids {
+            return None;
         }
+        let alias = format!("export type {name}Id = Brand<HelixID, '{name}'>;\n");
+        let field = format!(
+            "  {}{}: {name}Id;\n",
+            self.property_name("id"),
+            if optional { "?" } else { "" }
+        );
+        Some((alias, field))
+    }
 
-        if !self.schema.vectors.is_empty() {
-            let vector_names: Vec<String> = self.schema.vectors.keys().cloned().collect();
-            output.push_str(&format!(
-                "export type AnyVector = {};\n\n",
-                vector_names.join(" | ")
-            ));
+    fn generate_node_types(&self) -> String {
+        let mut output = String::from("// Node types\n");
+        for (name, node) in &self.schema.nodes {
+            output.push_str(&self.node_type(name, node));
         }
+        output.push_str(&Self::union_type("AnyNode", self.schema.nodes.keys()));
+        output
+    }
 
+    fn generate_vector_types(&self) -> String {
+        let mut output = String::from("// Vector types\n");
+        for (name, vector) in &self.schema.vectors {
+            output.push_str(&self.vector_type(name, vector));
+        }
+        output.push_str(&Self::union_type("AnyVector", self.schema.vectors.keys()));
         output
     }
 
     fn generate_edge_types(&self) -> String {
-        let mut output = String::new();
-        output.push_str("// Edge types\n");
-
+        let mut output = String::from("// Edge types\n");
         for (name, edge) in &self.schema.edges {
-            output.push_str(&format!("export interface {name} extends BaseEdge {{\n"));
-            output.push_str(&format!("  from: {};\n", edge.from_node));
-            output.push_str(&format!("  to: {};\n", edge.to_node));
+            output.push_str(&self.edge_type(name, edge));
+        }
+        output.push_str(&Self::union_type("AnyEdge", self.schema.edges.keys()));
+        output
+    }
 
-            for (prop_name, field_type) in &edge.properties {
-                let ts_type = Self::field_type_to_typescript(field_type);
-                output.push_str(&format!("  {prop_name}: {ts_type};\n"));
-            }
+    fn node_type(&self, name: &str, node: &NodeDefinition) -> String {
+        self.entity_interface(name, "BaseNode", false, &[], &node.properties)
+            + &self.input_types(name, &[], &node.properties, &node.defaults)
+    }
 
-            output.push_str("}\n\n");
-        }
+    fn vector_type(&self, name: &str, vector: &VectorDefinition) -> String {
+        self.entity_interface(name, "BaseVector", false, &[], &vector.properties)
+            + &self.input_types(name, &[], &vector.properties, &vector.defaults)
+    }
 
-        if !self.schema.edges.is_empty() {
-            let edge_names: Vec<String> = self.schema.edges.keys().cloned().collect();
-            output.push_str(&format!(
-                "export type AnyEdge = {};\n\n",
-                edge_names.join(" | ")
-            ));
+    fn edge_type(&self, name: &str, edge: &EdgeDefinition) -> String {
+        let from = Self::type_name(&edge.from_node);
+        let to = Self::type_name(&edge.to_node);
+        let endpoints = [("from", from.as_str()), ("to", to.as_str())];
+        self.entity_interface(name, "BaseEdge", true, &endpoints, &edge.properties)
+            + &self.input_types(name, &["from", "to"], &edge.properties, &edge.defaults)
+    }
+
+    /// `export interface {name} extends {base}`, preceded by its branded ID
+    /// type when branding. `fields` are already TypeScript types.
+    fn entity_interface(
+        &self,
+        name: &str,
+        base: &str,
+        optional_id: bool,
+        fields: &[(&str, &str)],
+        properties: &IndexMap<String, FieldType>,
+    ) -> String {
+        let mut output = String::new();
+
+        let branded_id = self.branded_id(name, optional_id);
+        if let Some((alias, _)) = &branded_id {
+            output.push_str(alias);
+        }
+        output.push_str(&format!(
+            "export interface {} extends {base} {{\n",
+            Self::type_name(name)
+        ));
+        if let Some((_, field)) = &branded_id {
+            output.push_str(field);
+        }
+        for (field_name, ts_type) in fields {
+            let key = self.property_name(field_name);
+            output.push_str(&format!("  {key}: {ts_type};\n"));
         }
+        for (prop_name, field_type) in properties {
+            output.push_str(&self.field(prop_name, field_type, None));
+        }
+        output.push_str("}\n\n");
+
+        output
+    }
+
+    /// `{name}Create`, `{name}Update` and `{name}Row`. `endpoints` are the
+    /// entity's fields that are not stored with it (an edge's nodes).
+    fn input_types(
+        &self,
+        name: &str,
+        endpoints: &[&str],
+        properties: &IndexMap<String, FieldType>,
+        defaults: &IndexMap<String, DefaultValue>,
+    ) -> String {
+        let mut output = self.create_interface(name, properties, defaults);
+        output.push_str(&format!(
+            "/** Properties for updating a `{name}`; all may be omitted. */\n\
+             export type {name}Update = Partial<{name}Create>;\n\n"
+        ));
+
+        let type_name = Self::type_name(name);
+        let omitted: Vec<String> = endpoints
+            .iter()
+            .filter(|end| !properties.contains_key(**end))
+            .map(|end| format!("'{}'", self.options.property_naming.apply(end)))
+            .collect();
+        let row = if omitted.is_empty() {
+            type_name
+        } else {
+            format!("Omit<{type_name}, {}>", omitted.join(" | "))
+        };
+        output.push_str(&format!(
+            "/** A `{name}` as stored: server-assigned fields and properties. */\n\
+             export type {name}Row = {row};\n\n"
+        ));
+        output
+    }
 
+    /// `export interface {name}Create`: the properties passed when creating
+    /// the entity, where optional and defaulted ones may be left out.
+    fn create_interface(
+        &self,
+        name: &str,
+        properties: &IndexMap<String, FieldType>,
+        defaults: &IndexMap<String, DefaultValue>,
+    ) -> String {
+        let mut output = format!(
+            "/** Properties for creating a `{name}`; defaulted ones may be omitted. */\n\
+             export interface {name}Create {{\n"
+        );
+        for (prop_name, field_type) in properties {
+            output.push_str(&self.field(prop_name, field_type, defaults.get(prop_name)));
+        }
+        output.push_str("}\n\n");
         output
     }
 
+    /// `export type AnyNode = User | Post;`, or nothing for no names.
+    fn union_type<'n>(alias: &str, names: impl Iterator<Item = &'n String>) -> String {
+        let names: Vec<String> = names.map(|name| Self::type_name(name)).collect();
+        if names.is_empty() {
+            return String::new();
+        }
+        format!("export type {alias} = {};\n\n", names.join(" | "))
+    }
+
     fn generate_query_types(&self) -> String {
         let mut output = String::new();
-        output.push_str("// Query parameter types\n");
-        output.push_str("// Note: Return types are not generated as they cannot be inferred from schema introspection\n\n");
+        output.push_str("// Query parameter and result types\n");
 
         if !self.schema.queries.is_empty() {
             for (name, query) in &self.schema.queries {
                 let param_type_name = format!("{}Params", to_pascal_case(name));
-                output.push_str(&format!("export interface {param_type_name} {{\n"));
+                let input_type = self.params_input_type(query);
+                match &input_type {
+                    Some((input_type, _)) => output.push_str(&format!(
+                        "export interface {param_type_name} extends {input_type} {{\n"
+                    )),
+                    None => output.push_str(&format!("export interface {param_type_name} {{\n")),
+                }
 
                 for (param_name, field_type) in &query.parameters {
-                    let ts_type = Self::field_type_to_typescript(field_type);
-                    output.push_str(&format!("  {param_name}: {ts_type};\n"));
+                    if input_type
+                        .as_ref()
+                        .is_some_and(|(_, properties)| properties.contains_key(param_name))
+                    {
+                        continue;
+                    }
+                    output.push_str(&self.field(param_name, field_type, None));
+                }
+
+                output.push_str("}\n\n");
+
+                let result_type_name = format!("{}Result", to_pascal_case(name));
+                output.push_str(&format!("export interface {result_type_name} {{\n"));
+
+                for field in &query.returns {
+                    let ts_type = self.return_type_to_typescript(&field.return_type);
+                    output.push_str(&format!(
+                        "  {}: {ts_type};\n",
+                        self.property_name(&field.name)
+                    ));
                 }
 
                 output.push_str("}\n\n");
@@ -173,6 +916,79 @@ export interface BaseEdge {
         output
     }
 
+    /// The `{Name}Create` or `{Name}Update` type whose fields are exactly the
+    /// query's parameters (same names and types, optional where the input
+    /// type's are), apart from IDs the query uses to look up that entity,
+    /// with that entity's properties. `None` unless exactly one entity with
+    /// at least two properties matches.
+    fn params_input_type<'s>(
+        &'s self,
+        query: &QueryDefinition,
+    ) -> Option<(String, &'s IndexMap<String, FieldType>)> {
+        let entities = (self.schema.nodes.iter())
+            .map(|(name, node)| (name, &node.properties, &node.defaults))
+            .chain(
+                (self.schema.vectors.iter())
+                    .map(|(name, vector)| (name, &vector.properties, &vector.defaults)),
+            )
+            .chain(
+                (self.schema.edges.iter())
+                    .map(|(name, edge)| (name, &edge.properties, &edge.defaults)),
+            );
+
+        let mut matches = Vec::new();
+        for (name, properties, defaults) in entities {
+            // A single property says too little about what the query does
+            if properties.len() < 2 {
+                continue;
+            }
+            // Besides the properties, only IDs used to look up this entity
+            let lookup = FieldType::EntityID(name.clone());
+            let other_params = (query.parameters.iter())
+                .filter(|(param, _)| !properties.contains_key(*param))
+                .all(|(_, field_type)| split_optional(field_type).0 == &lookup);
+            if !other_params {
+                continue;
+            }
+            // Whether every parameter is omittable, and whether each is
+            // omittable exactly when the property is on create.
+            let (mut update, mut create) = (true, true);
+            for (property, field_type) in properties {
+                let (property_type, optional) = split_optional(field_type);
+                let Some((param_type, param_optional)) =
+                    query.parameters.get(property).map(split_optional)
+                else {
+                    (update, create) = (false, false);
+                    break;
+                };
+                if param_type != property_type {
+                    (update, create) = (false, false);
+                    break;
+                }
+                update &= param_optional;
+                create &= param_optional == (optional || defaults.contains_key(property));
+            }
+            if create {
+                matches.push((format!("{name}Create"), properties));
+            } else if update {
+                matches.push((format!("{name}Update"), properties));
+            }
+        }
+
+        match matches.len() {
+            1 => matches.pop(),
+            _ => None,
+        }
+    }
+
+    /// Trailing parameter for per-query overrides, if the client takes them.
+    fn request_options_param(&self) -> &'static str {
+        match self.options.client {
+            ClientKind::HelixTs => "",
+            ClientKind::Fetch => ", request?: RequestOptions",
+        }
+    }
+
     fn generate_client_interface(&self) -> String {
         let mut output = String::new();
         output.push_str("// Client interface for type-safe HelixDB operations\n");
@@ -182,14 +998,15 @@ export interface BaseEdge {
 
             for (name, query) in &self.schema.queries {
                 let param_type = format!("{}Params", to_pascal_case(name));
+                let result_type = format!("{}Result", to_pascal_case(name));
 
                 if let Some(ref description) = query.description {
                     output.push_str(&format!("  /**\n   * {description}\n   */\n"));
                 }
 
-                // Use the actual HelixDBResponse type since we can't infer return types
                 output.push_str(&format!(
-                    "  {name}(params: {param_type}): Promise<HelixDBResponse>;\n"
+                    "  {name}(params: {param_type}{}): Promise<{result_type}>;\n",
+                    self.request_options_param()
                 ));
             }
 
@@ -197,9 +1014,10 @@ export interface BaseEdge {
         } else {
             output.push_str("// No queries found in schema\n");
             output.push_str("export interface TypedHelixDBClient {\n");
-            output.push_str(
-                "  query(endpoint: string, params: HelixDBInput): Promise<HelixDBResponse>;\n",
-            );
+            output.push_str(&format!(
+                "  query(endpoint: string, params: HelixDBInput{}): Promise<HelixDBResponse>;\n",
+                self.request_options_param()
+            ));
             output.push_str("}\n\n");
         }
 
@@ -210,102 +1028,1524 @@ export interface BaseEdge {
         let mut output = String::new();
 
         output.push_str("// SDK Integration Helpers\n");
+        output.push_str(
+            r#"/**
+ * Credentials for HelixDB instances behind an API gateway
+ * (same fields as the `[auth]` section of helix-ts-gen.toml)
+ */
+export interface HelixAuthConfig {
+  /** Sent as `Authorization: Bearer <token>` */
+  bearerToken?: string;
+  apiKey?: string;
+  /** Header carrying `apiKey` (default: `x-api-key`) */
+  apiKeyHeader?: string;
+  /** Extra headers, sent as-is */
+  headers?: Record<string, string>;
+}
 
-        output.push_str("/**\n");
-        output.push_str(" * Create a type-safe HelixDB client wrapper\n");
-        output.push_str(" * Usage:\n");
-        output.push_str(" *   const client = new HelixDB();\n");
-        output.push_str(" *   const typedClient = createTypedClient(client);\n");
-        output.push_str(" */\n");
-        output
-            .push_str("export function createTypedClient(client: HelixDB): TypedHelixDBClient {\n");
-        output.push_str("  return {\n");
+export function helixAuthHeaders(auth: HelixAuthConfig = {}): Record<string, string> {
+  const headers: Record<string, string> = { ...auth.headers };
+  if (auth.apiKey) headers[auth.apiKeyHeader ?? 'x-api-key'] = auth.apiKey;
+  if (auth.bearerToken) headers['Authorization'] = `Bearer ${auth.bearerToken}`;
+  return headers;
+}
+
+"#,
+        );
+        output.push_str(&self.generate_conversions());
+        match self.options.client {
+            ClientKind::HelixTs => output.push_str(&self.generate_helix_ts_client()),
+            ClientKind::Fetch => output.push_str(&self.generate_fetch_client()),
+        }
 
+        // Generate query endpoint constants for better DX
         if !self.schema.queries.is_empty() {
+            output.push_str("// Query endpoint constants\n");
+            output.push_str("export const QueryEndpoints = {\n");
             for name in self.schema.queries.keys() {
-                output.push_str(&format!("    async {name}(params) {{\n"));
                 output.push_str(&format!(
-                    "      return await client.query('{name}', params);\n"
+                    "  {}: '{}',\n",
+                    to_screaming_snake_case(name),
+                    name
                 ));
-                output.push_str("    },\n");
             }
-        } else {
-            output.push_str("    async query(endpoint: string, params: HelixDBInput): Promise<HelixDBResponse> {\n");
-            output.push_str("      return await client.query(endpoint, params);\n");
-            output.push_str("    },\n");
+            output.push_str("} as const;\n\n");
         }
 
-        output.push_str("  };\n");
+        output.push_str("/**\n");
+        output.push_str(
+            " * Helper to extract data from HelixDB response with basic type assertion\n",
+        );
+        output.push_str(" * Usage: const users = extractData<User[]>(response, 'users');\n");
+        output.push_str(" */\n");
+        output.push_str(
+            "export function extractData<T>(response: HelixDBResponse, key?: string): T {\n",
+        );
+        output.push_str("  if (key) {\n");
+        output.push_str("    return response[key] as T;\n");
+        output.push_str("  }\n");
+        output.push_str("  return response as T;\n");
         output.push_str("}\n\n");
 
-        // Generate query endpoint constants for better DX
-        if !self.schema.queries.is_empty() {
-            output.push_str("// Query endpoint constants\n");
-            output.push_str("export const QueryEndpoints = {\n");
-            for name in self.schema.queries.keys() {
-                output.push_str(&format!(
-                    "  {}: '{}',\n",
-                    to_snake_case(name).to_uppercase(),
-                    name
-                ));
+        output
+    }
+
+    /// `is{Name}` (and with `assertions`, `assert{Name}`) for every node,
+    /// vector and edge, checking each field's runtime type.
+    fn generate_type_guards(&self) -> String {
+        let mut output = String::from("// Type guards and validation helpers\n");
+        output.push_str(
+            r#"type Check = (value: unknown, path: string) => string | undefined;
+
+function describe(value: unknown): string {
+  if (value === null) return 'null';
+  if (Array.isArray(value)) return 'array';
+  return typeof value;
+}
+
+function expectType(what: string, test: (value: unknown) => boolean): Check {
+  return (value, path) => (test(value) ? undefined : `${path}: expected ${what}, got ${describe(value)}`);
+}
+
+const checkString = expectType('string', (value) => typeof value === 'string');
+const checkNumber = expectType('number', (value) => typeof value === 'number');
+const checkInteger = expectType('integer', (value) => Number.isInteger(value));
+const checkBigint = expectType('bigint', (value) => typeof value === 'bigint');
+const checkBoolean = expectType('boolean', (value) => typeof value === 'boolean');
+const checkDate = expectType('Date', (value) => value instanceof Date && !isNaN(value.getTime()));
+const checkUnknown: Check = () => undefined;
+
+function checkOptional(check: Check): Check {
+  return (value, path) => (value === undefined || value === null ? undefined : check(value, path));
+}
+
+function checkArray(check: Check, length?: number): Check {
+  return (value, path) => {
+    if (!Array.isArray(value)) return `${path}: expected array, got ${describe(value)}`;
+    if (length !== undefined && value.length !== length) {
+      return `${path}: expected ${length} elements, got ${value.length}`;
+    }
+    for (let i = 0; i < value.length; i++) {
+      const error = check(value[i], `${path}[${i}]`);
+      if (error) return error;
+    }
+    return undefined;
+  };
+}
+
+function checkFields(value: unknown, path: string, fields: Record<string, Check>): string | undefined {
+  if (typeof value !== 'object' || value === null || Array.isArray(value)) {
+    return `${path}: expected object, got ${describe(value)}`;
+  }
+  for (const [key, check] of Object.entries(fields)) {
+    const error = check((value as Record<string, unknown>)[key], `${path}.${key}`);
+    if (error) return error;
+  }
+  return undefined;
+}
+
+"#,
+        );
+
+        let mut guards: Vec<(&String, Vec<(String, String)>)> = Vec::new();
+        for (name, node) in &self.schema.nodes {
+            guards.push((name, self.field_checks("BaseNode", &node.properties)));
+        }
+        for (name, vector) in &self.schema.vectors {
+            guards.push((name, self.field_checks("BaseVector", &vector.properties)));
+        }
+        for (name, edge) in &self.schema.edges {
+            let mut checks = self.field_checks("BaseEdge", &edge.properties);
+            for (end, target) in [("from", &edge.from_node), ("to", &edge.to_node)] {
+                if !edge.properties.contains_key(end) {
+                    checks.push((end.to_string(), format!("validate{target}")));
+                }
             }
-            output.push_str("} as const;\n\n");
+            guards.push((name, checks));
         }
 
-        output.push_str("// Type guards and validation helpers\n");
-        for name in self.schema.nodes.keys() {
+        for (name, checks) in &guards {
             output.push_str(&format!(
-                "export function is{name}(obj: any): obj is {name} {{\n"
+                "function validate{name}(value: unknown, path: string): string | undefined {{\n"
             ));
-            output.push_str(
-                "  return obj && typeof obj === 'object' && typeof obj.id === 'number';\n",
-            );
+            output.push_str("  return checkFields(value, path, {\n");
+            for (field, check) in checks {
+                output.push_str(&format!("    {}: {check},\n", self.property_name(field)));
+            }
+            output.push_str("  });\n");
             output.push_str("}\n\n");
         }
 
-        for name in self.schema.vectors.keys() {
+        for (name, _) in &guards {
+            let type_name = Self::type_name(name);
             output.push_str(&format!(
-                "export function is{name}(obj: any): obj is {name} {{\n"
+                "export function is{name}(value: unknown): value is {type_name} {{\n"
             ));
+            output.push_str(&format!(
+                "  return validate{name}(value, '{name}') === undefined;\n"
+            ));
+            output.push_str("}\n\n");
+
+            if self.options.assertions {
+                output.push_str(&format!(
+                    "/** Throws a TypeError naming the first field that does not match {name} */\n"
+                ));
+                output.push_str(&format!(
+                    "export function assert{name}(value: unknown): asserts value is {type_name} {{\n"
+                ));
+                output.push_str(&format!(
+                    "  const error = validate{name}(value, '{name}');\n"
+                ));
+                output.push_str("  if (error) throw new TypeError(error);\n");
+                output.push_str("}\n\n");
+            }
+        }
+
+        output
+    }
+
+    /// Base fields not overridden by a property, then the properties, each
+    /// with the expression that checks it.
+    fn field_checks(
+        &self,
+        base: &str,
+        properties: &IndexMap<String, FieldType>,
+    ) -> Vec<(String, String)> {
+        base_fields(base)
+            .iter()
+            .filter(|(name, _)| !properties.contains_key(*name))
+            .map(|(name, field_type)| (name.to_string(), self.field_check(field_type)))
+            .chain(
+                properties
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), self.field_check(field_type))),
+            )
+            .collect()
+    }
+
+    fn field_check(&self, field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::String | FieldType::ID | FieldType::EntityID(_) => "checkString".to_string(),
+            FieldType::Integer(integer_type) => {
+                match self.options.large_integers.typescript_type(*integer_type) {
+                    "bigint" => "checkBigint",
+                    "string" => "checkString",
+                    _ => "checkInteger",
+                }
+                .to_string()
+            }
+            FieldType::Float(_) => "checkNumber".to_string(),
+            FieldType::Boolean => "checkBoolean".to_string(),
+            FieldType::Date => match self.options.dates {
+                DateRepresentation::IsoString => "checkString",
+                DateRepresentation::Date => "checkDate",
+                DateRepresentation::Epoch => "checkNumber",
+            }
+            .to_string(),
+            FieldType::Vector(dim) => format!("checkArray(checkNumber, {dim})"),
+            FieldType::Array(inner) => format!("checkArray({})", self.field_check(inner)),
+            FieldType::Optional(inner) => format!("checkOptional({})", self.field_check(inner)),
+            FieldType::Custom(_) => "checkUnknown".to_string(),
+        }
+    }
+
+    /// `createTypedClient` wrapping a `helix-ts` client.
+    fn generate_helix_ts_client(&self) -> String {
+        let mut output = String::from(self.json_helpers());
+        let (body, response) = self.json_calls();
+
+        output.push_str(
+            r#"function authenticatedQuery(url: string, auth: HelixAuthConfig) {
+  return async (endpoint: string, params: HelixDBInput): Promise<HelixDBResponse> => {
+    const response = await fetch(`${url.replace(/\/$/, '')}/${endpoint}`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', ...helixAuthHeaders(auth) },
+"#,
+        );
+        output.push_str(&format!("      body: {body},\n"));
+        output.push_str(
+            r#"    });
+    if (!response.ok) {
+      throw new Error(`HelixDB query ${endpoint} failed: ${response.status} ${await response.text()}`);
+    }
+"#,
+        );
+        output.push_str(&format!("    return {response} as HelixDBResponse;\n"));
+        output.push_str("  };\n}\n\n");
+
+        output.push_str("export interface TypedClientOptions {\n");
+        if self.options.zod {
             output.push_str(
-                "  return obj && typeof obj === 'object' && typeof obj.id === 'bigint';\n",
+                "  /** Validate params with the generated Zod schemas before each query */\n",
             );
-            output.push_str("}\n\n");
+            output.push_str("  validate?: boolean;\n");
+        }
+        if self.exact_integers() {
+            output.push_str(&self.generate_exact_helix_ts_client());
+            return output;
+        }
+
+        output.push_str("export interface TypedClientOptions {\n");
+        if self.options.zod {
+            output.push_str(
+                "  /** Validate params with the generated Zod schemas before each query */\n",
+            );
+            output.push_str("  validate?: boolean;\n");
         }
+        output.push_str("  /**\n");
+        output.push_str(
+            "   * Send queries with these credentials. helix-ts cannot attach headers,\n",
+        );
+        output.push_str("   * so queries are then POSTed to `url` with `fetch` instead.\n");
+        output.push_str("   */\n");
+        output.push_str("  auth?: HelixAuthConfig;\n");
+        output.push_str("  /** Base URL of the HelixDB instance; required with `auth` */\n");
+        output.push_str("  url?: string;\n");
+        output.push_str("}\n\n");
+
+        output.push_str(
+            r#"function clientQuery(client: HelixDB, options: TypedClientOptions) {
+  if (!options.auth) {
+    return (endpoint: string, params: HelixDBInput) => client.query(endpoint, params);
+  }
+  if (options.url === undefined) {
+    throw new TypeError('createTypedClient: `url` is required with `auth`, since helix-ts cannot send headers');
+  }
+  return authenticatedQuery(options.url, options.auth);
+}
+
+"#,
+        );
 
         output.push_str("/**\n");
+        output.push_str(" * Create a type-safe HelixDB client wrapper\n");
+        output.push_str(" * Usage:\n");
+        output.push_str(" *   const client = new HelixDB();\n");
+        output.push_str(" *   const typedClient = createTypedClient(client);\n");
+        if self.options.zod {
+            output.push_str(
+                " *   const validatingClient = createTypedClient(client, { validate: true });\n",
+            );
+        }
         output.push_str(
-            " * Helper to extract data from HelixDB response with basic type assertion\n",
+            " *   const gatewayClient = createTypedClient(client, { url, auth: { apiKey } });\n",
         );
-        output.push_str(" * Usage: const users = extractData<User[]>(response, 'users');\n");
         output.push_str(" */\n");
+        output.push_str("export function createTypedClient(client: HelixDB, options: TypedClientOptions = {}): TypedHelixDBClient {\n");
+        output.push_str("  const query = clientQuery(client, options);\n");
+        output.push_str(&self.generate_client_methods(""));
+        output.push_str("}\n\n");
+
+        output
+    }
+
+    /// `createTypedClient` for the `helix-ts` client when wide integers must
+    /// stay exact. helix-ts parses responses itself, rounding them, so
+    /// queries are POSTed to the required `url` with `fetch` instead.
+    fn generate_exact_helix_ts_client(&self) -> String {
+        let mut output = String::from("export interface TypedClientOptions {\n");
+        if self.options.zod {
+            output.push_str(
+                "  /** Validate params with the generated Zod schemas before each query */\n",
+            );
+            output.push_str("  validate?: boolean;\n");
+        }
+        output.push_str("  /** Base URL of the HelixDB instance */\n");
+        output.push_str("  url: string;\n");
+        output.push_str("  auth?: HelixAuthConfig;\n");
+        output.push_str("}\n\n");
+
+        output.push_str("/**\n");
+        output
+            .push_str(" * Create a type-safe HelixDB client. helix-ts parses responses as plain\n");
+        output
+            .push_str(" * JSON, rounding integers above 2^53, so queries are sent with `fetch`.\n");
+        output.push_str(" * Usage:\n");
+        output.push_str(" *   const typedClient = createTypedClient({ url });\n");
+        if self.options.zod {
+            output.push_str(
+                " *   const validatingClient = createTypedClient({ url, validate: true });\n",
+            );
+        }
+        output
+            .push_str(" *   const gatewayClient = createTypedClient({ url, auth: { apiKey } });\n");
+        output.push_str(" */\n");
+        output.push_str("export function createTypedClient(options: TypedClientOptions): TypedHelixDBClient {\n");
+        output.push_str("  const query = authenticatedQuery(options.url, options.auth ?? {});\n");
+        output.push_str(&self.generate_client_methods(""));
+        output.push_str("}\n\n");
+
+        output
+    }
+
+    /// A dependency-free `createTypedClient` that POSTs to `/{QueryName}`
+    /// with `fetch`.
+    fn generate_fetch_client(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("export interface TypedClientOptions {\n");
         output.push_str(
-            "export function extractData<T>(response: HelixDBResponse, key?: string): T {\n",
+            "  /** Base URL of the HelixDB instance (default: http://localhost:6969) */\n",
         );
-        output.push_str("  if (key) {\n");
-        output.push_str("    return response[key] as T;\n");
-        output.push_str("  }\n");
-        output.push_str("  return response as T;\n");
+        output.push_str("  baseUrl?: string;\n");
+        output.push_str("  /** Headers sent with every query */\n");
+        output.push_str("  headers?: Record<string, string>;\n");
+        output.push_str("  auth?: HelixAuthConfig;\n");
+        output.push_str("  /** Abort each query after this many milliseconds */\n");
+        output.push_str("  timeoutMs?: number;\n");
+        output.push_str("  /** Aborts every in-flight query when signalled */\n");
+        output.push_str("  signal?: AbortSignal;\n");
+        output.push_str("  /** `fetch` implementation (default: the global one) */\n");
+        output.push_str("  fetch?: typeof fetch;\n");
+        if self.options.zod {
+            output.push_str(
+                "  /** Validate params with the generated Zod schemas before each query */\n",
+            );
+            output.push_str("  validate?: boolean;\n");
+        }
+        output.push_str("}\n\n");
+
+        output.push_str(self.json_helpers());
+        output.push_str(
+            r#"/** Per-query overrides */
+export interface RequestOptions {
+  headers?: Record<string, string>;
+  timeoutMs?: number;
+  signal?: AbortSignal;
+}
+
+/** Thrown when HelixDB answers a query with a non-2xx status */
+export class HelixDBError extends Error {
+  readonly endpoint: string;
+  readonly status: number;
+  readonly body: string;
+
+  constructor(endpoint: string, status: number, body: string) {
+    super(`HelixDB query ${endpoint} failed with status ${status}: ${body}`);
+    this.name = 'HelixDBError';
+    this.endpoint = endpoint;
+    this.status = status;
+    this.body = body;
+  }
+}
+
+function createQuery(options: TypedClientOptions) {
+  const baseUrl = (options.baseUrl ?? 'http://localhost:6969').replace(/\/+$/, '');
+  const fetchImpl = options.fetch ?? globalThis.fetch;
+  return async (endpoint: string, params: HelixDBInput, request: RequestOptions = {}): Promise<HelixDBResponse> => {
+    const signals: AbortSignal[] = [];
+    if (options.signal) signals.push(options.signal);
+    if (request.signal) signals.push(request.signal);
+    const timeoutMs = request.timeoutMs ?? options.timeoutMs;
+    if (timeoutMs !== undefined) signals.push(AbortSignal.timeout(timeoutMs));
+
+    const response = await fetchImpl(`${baseUrl}/${endpoint}`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        ...options.headers,
+        ...helixAuthHeaders(options.auth),
+        ...request.headers,
+      },
+"#,
+        );
+        let (body, response) = self.json_calls();
+        output.push_str(&format!("      body: {body},\n"));
+        output.push_str(
+            r#"      signal: signals.length > 1 ? AbortSignal.any(signals) : signals[0],
+    });
+    if (!response.ok) {
+      throw new HelixDBError(endpoint, response.status, await response.text());
+    }
+"#,
+        );
+        output.push_str(&format!("    return {response} as HelixDBResponse;\n"));
+        output.push_str("  };\n}\n\n");
+
+        output.push_str("/**\n");
+        output.push_str(" * Create a type-safe HelixDB client\n");
+        output.push_str(" * Usage:\n");
+        output.push_str(
+            " *   const client = createTypedClient({ baseUrl: 'http://localhost:6969' });\n",
+        );
+        output.push_str(" *   const gatewayClient = createTypedClient({ baseUrl, auth: { apiKey }, timeoutMs: 5000 });\n");
+        output.push_str(" */\n");
+        output.push_str("export function createTypedClient(options: TypedClientOptions = {}): TypedHelixDBClient {\n");
+        output.push_str("  const query = createQuery(options);\n");
+        output.push_str(&self.generate_client_methods(", request"));
         output.push_str("}\n\n");
 
         output
     }
 
-    fn field_type_to_typescript(field_type: &FieldType) -> String {
+    /// The object literal returned by `createTypedClient`; `extra_args` are
+    /// passed through to `query` after the params.
+    fn generate_client_methods(&self, extra_args: &str) -> String {
+        let mut output = String::from("  return {\n");
+
+        if !self.schema.queries.is_empty() {
+            for (name, query) in &self.schema.queries {
+                output.push_str(&format!("    async {name}(params{extra_args}) {{\n"));
+                if self.options.zod {
+                    output.push_str(&format!(
+                        "      if (options.validate) {}ParamsSchema.parse(params);\n",
+                        to_pascal_case(name)
+                    ));
+                }
+                let params = match self.params_conversion(query) {
+                    Some(fields) => format!("convertObject(params, {fields})"),
+                    None => "params".to_string(),
+                };
+                let response = format!("await query('{name}', {params}{extra_args})");
+                let result = match self.fields_conversion(&query.returns) {
+                    Some(fields) => format!("convertObject({response}, {fields})"),
+                    None => format!("({response})"),
+                };
+                output.push_str(&format!(
+                    "      return {result} as {}Result;\n",
+                    to_pascal_case(name)
+                ));
+                output.push_str("    },\n");
+            }
+        } else {
+            output.push_str(&format!(
+                "    async query(endpoint: string, params: HelixDBInput{}): Promise<HelixDBResponse> {{\n",
+                if extra_args.is_empty() { "" } else { ", request?: RequestOptions" }
+            ));
+            output.push_str(&format!(
+                "      return await query(endpoint, params{extra_args});\n"
+            ));
+            output.push_str("    },\n");
+        }
+
+        output.push_str("  };\n");
+        output
+    }
+
+    /// `convertObject` and an exported `decode{Name}` for each entity whose
+    /// field names or types differ from what HelixDB sends; nothing if none
+    /// do.
+    fn generate_conversions(&self) -> String {
+        let mut body = String::new();
+        let entities = self
+            .schema
+            .nodes
+            .keys()
+            .chain(self.schema.vectors.keys())
+            .chain(self.schema.edges.keys());
+        for name in entities {
+            let Some(fields) = self.entity_conversion(name) else {
+                continue;
+            };
+            body.push_str(&format!(
+                "/** Converts a {name} as returned by HelixDB to the generated type */\n"
+            ));
+            body.push_str(&format!(
+                "export function decode{name}(value: any): {} {{\n",
+                Self::type_name(name)
+            ));
+            body.push_str(&format!("  return convertObject(value, {fields});\n"));
+            body.push_str("}\n\n");
+        }
+        let results: Vec<String> = self
+            .schema
+            .queries
+            .values()
+            .filter_map(|query| self.fields_conversion(&query.returns))
+            .collect();
+        let params: Vec<String> = self
+            .schema
+            .queries
+            .values()
+            .filter_map(|query| self.params_conversion(query))
+            .collect();
+        if body.is_empty() && results.is_empty() && params.is_empty() {
+            return String::new();
+        }
+        let uses = |helper: &str| {
+            body.contains(helper) || results.iter().chain(&params).any(|f| f.contains(helper))
+        };
+
+        let mut output = String::from("// Conversion between HelixDB's and the generated fields\n");
+        output.push_str(
+            r#"type Convert = (value: any) => any;
+
+/** Renames the keys of an object per `fields` (`{ from: [to, convert?] }`) */
+function convertObject(value: any, fields: Record<string, [string, Convert?]>): any {
+  if (typeof value !== 'object' || value === null || Array.isArray(value)) return value;
+  const converted: Record<string, unknown> = {};
+  for (const [key, item] of Object.entries(value)) {
+    const entry = Object.prototype.hasOwnProperty.call(fields, key) ? fields[key] : undefined;
+    const [name, convert]: [string, Convert?] = entry ?? [key];
+    converted[name] = convert ? convert(item) : item;
+  }
+  return converted;
+}
+
+"#,
+        );
+        if uses("convertArray(") {
+            output.push_str(
+                r#"function convertArray(convert: Convert): Convert {
+  return (value) => (Array.isArray(value) ? value.map(convert) : value);
+}
+
+"#,
+            );
+        }
+        if uses("reviveDate") {
+            output.push_str(
+                r#"function reviveDate(value: any): any {
+  return typeof value === 'string' || typeof value === 'number' ? new Date(value) : value;
+}
+
+"#,
+            );
+        }
+        if uses("parseEpoch") {
+            output.push_str(
+                r#"function parseEpoch(value: any): any {
+  return typeof value === 'string' ? Date.parse(value) : value;
+}
+
+"#,
+            );
+        }
+        if uses("serializeDate") {
+            output.push_str(
+                r#"/** A `Date` or epoch milliseconds as the RFC 3339 string HelixDB expects */
+function serializeDate(value: any): any {
+  return value instanceof Date || typeof value === 'number' ? new Date(value).toISOString() : value;
+}
+
+"#,
+            );
+        }
+        if uses("reviveBigint") {
+            output.push_str(
+                r#"function reviveBigint(value: any): any {
+  return typeof value === 'number' || typeof value === 'string' ? BigInt(value) : value;
+}
+
+"#,
+            );
+        }
+        if uses("reviveIntegerString") {
+            output.push_str(
+                r#"function reviveIntegerString(value: any): any {
+  return typeof value === 'number' ? BigInt(value).toString() : value;
+}
+
+"#,
+            );
+        }
+        if uses("serializeInteger") {
+            output.push_str(
+                r#"/** A decimal string as a `bigint`, which `stringifyJson` writes as a JSON number */
+function serializeInteger(value: any): any {
+  return typeof value === 'string' && /^-?\d+$/.test(value) ? BigInt(value) : value;
+}
+
+"#,
+            );
+        }
+        output.push_str(&body);
+        output
+    }
+
+    /// `parseJson` and `stringifyJson`, when wide integers must stay exact.
+    fn json_helpers(&self) -> &'static str {
+        if !self.exact_integers() {
+            return "";
+        }
+        r#"/** JSON.parse, keeping integers a `number` cannot hold exactly as decimal strings */
+function parseJson(text: string): any {
+  const exact = text.replace(/"(?:[^"\\]|\\.)*"|-?\d+(?:\.\d+)?(?:[eE][+-]?\d+)?/g, (token) =>
+    /^-?\d+$/.test(token) && !Number.isSafeInteger(Number(token)) ? `"${token}"` : token,
+  );
+  return JSON.parse(exact);
+}
+
+/** JSON.stringify, writing `bigint` values as JSON numbers */
+function stringifyJson(value: unknown): string {
+  const json = JSON.stringify(value, (_key, item) =>
+    typeof item === 'bigint' ? `\u0000bigint:${item}` : item,
+  );
+  return json.replace(/"\\u0000bigint:(-?\d+)"/g, '$1');
+}
+
+"#
+    }
+
+    /// How the client serializes `params` and reads `response`.
+    fn json_calls(&self) -> (&'static str, &'static str) {
+        if self.exact_integers() {
+            ("stringifyJson(params)", "parseJson(await response.text())")
+        } else {
+            ("JSON.stringify(params)", "(await response.json())")
+        }
+    }
+
+    /// Renames from HelixDB's field names to the generated ones, and
+    /// converts dates and wide integers, for an entity's base fields,
+    /// endpoints and properties.
+    fn entity_conversion(&self, name: &str) -> Option<String> {
+        let (base, properties, endpoints) = if let Some(node) = self.schema.nodes.get(name) {
+            ("BaseNode", &node.properties, Vec::new())
+        } else if let Some(vector) = self.schema.vectors.get(name) {
+            ("BaseVector", &vector.properties, Vec::new())
+        } else {
+            let edge = self.schema.edges.get(name)?;
+            let endpoints = vec![("from", &edge.from_node), ("to", &edge.to_node)];
+            ("BaseEdge", &edge.properties, endpoints)
+        };
+        let base = base_fields(base)
+            .into_iter()
+            .filter(|(field, _)| !properties.contains_key(*field))
+            .map(|(field, field_type)| {
+                self.decoding(field, self.field_conversion(&field_type, false))
+            });
+        let endpoints = endpoints
+            .into_iter()
+            .filter(|(end, _)| !properties.contains_key(*end))
+            .map(|(end, target)| {
+                let convert = self
+                    .entity_conversion(target)
+                    .map(|_| format!("decode{target}"));
+                self.decoding(end, convert)
+            });
+        let properties = properties.iter().map(|(field, field_type)| {
+            self.decoding(field, self.field_conversion(field_type, false))
+        });
+        Self::conversion_map(base.chain(endpoints).chain(properties))
+    }
+
+    /// Renames from the generated parameter names to the schema's, and
+    /// serializes dates and integer strings.
+    fn params_conversion(&self, query: &QueryDefinition) -> Option<String> {
+        Self::conversion_map(query.parameters.iter().map(|(param, field_type)| {
+            (
+                self.options.property_naming.apply(param),
+                param.clone(),
+                self.field_conversion(field_type, true),
+            )
+        }))
+    }
+
+    /// Renames from HelixDB's field names to the generated ones, converting
+    /// nested entities.
+    fn fields_conversion(&self, fields: &[ReturnField]) -> Option<String> {
+        Self::conversion_map(
+            fields.iter().map(|field| {
+                self.decoding(&field.name, self.return_conversion(&field.return_type))
+            }),
+        )
+    }
+
+    /// A function converting a value of `return_type` as returned by
+    /// HelixDB, if it contains renamed fields or dates.
+    fn return_conversion(&self, return_type: &ReturnType) -> Option<String> {
+        match return_type {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => self
+                .entity_conversion(name)
+                .map(|_| format!("decode{name}")),
+            ReturnType::Array(inner) => self
+                .return_conversion(inner)
+                .map(|convert| format!("convertArray({convert})")),
+            ReturnType::Object(fields) => self
+                .fields_conversion(fields)
+                .map(|fields| format!("(value) => convertObject(value, {fields})")),
+            ReturnType::Scalar(field_type) => self.field_conversion(field_type, false),
+            ReturnType::Unknown => None,
+        }
+    }
+
+    /// A function converting a value of `field_type` from what HelixDB sends
+    /// (after `parseJson`) or, when `encode`, to what it expects (before
+    /// `stringifyJson`), if the generated type differs.
+    fn field_conversion(&self, field_type: &FieldType, encode: bool) -> Option<String> {
+        match field_type {
+            FieldType::Date => match (self.options.dates, encode) {
+                (DateRepresentation::IsoString, _) => None,
+                (_, true) => Some("serializeDate".to_string()),
+                (DateRepresentation::Date, false) => Some("reviveDate".to_string()),
+                (DateRepresentation::Epoch, false) => Some("parseEpoch".to_string()),
+            },
+            FieldType::Integer(integer_type) if !integer_type.fits_in_f64() => {
+                match (self.options.large_integers, encode) {
+                    // `stringifyJson` writes bigints as numbers
+                    (LargeIntegerPolicy::Number, _) | (LargeIntegerPolicy::Bigint, true) => None,
+                    (LargeIntegerPolicy::Bigint, false) => Some("reviveBigint".to_string()),
+                    (LargeIntegerPolicy::String, true) => Some("serializeInteger".to_string()),
+                    (LargeIntegerPolicy::String, false) => Some("reviveIntegerString".to_string()),
+                }
+            }
+            FieldType::Optional(inner) => self.field_conversion(inner, encode),
+            FieldType::Array(inner) => self
+                .field_conversion(inner, encode)
+                .map(|convert| format!("convertArray({convert})")),
+            _ => None,
+        }
+    }
+
+    /// A field as named by HelixDB, its generated name and its converter.
+    fn decoding(&self, field: &str, convert: Option<String>) -> (String, String, Option<String>) {
+        (
+            field.to_string(),
+            self.options.property_naming.apply(field),
+            convert,
+        )
+    }
+
+    /// `{ from: ['to', convert] }` for the fields that are renamed or
+    /// converted, or `None` if none are.
+    fn conversion_map(
+        fields: impl Iterator<Item = (String, String, Option<String>)>,
+    ) -> Option<String> {
+        let entries: Vec<String> = fields
+            .filter(|(from, to, convert)| from != to || convert.is_some())
+            .map(|(from, to, convert)| {
+                let to = match property_key(&to) {
+                    key if key == to => format!("'{to}'"),
+                    quoted => quoted,
+                };
+                match convert {
+                    Some(convert) => format!("{}: [{to}, {convert}]", property_key(&from)),
+                    None => format!("{}: [{to}]", property_key(&from)),
+                }
+            })
+            .collect();
+        (!entries.is_empty()).then(|| format!("{{ {} }}", entries.join(", ")))
+    }
+
+    fn field_type_to_typescript(&self, field_type: &FieldType) -> String {
         match field_type {
             FieldType::String => "string".to_string(),
-            FieldType::Integer => "number".to_string(),
-            FieldType::Float => "number".to_string(),
+            FieldType::Integer(integer_type) => self
+                .options
+                .large_integers
+                .typescript_type(*integer_type)
+                .to_string(),
+            FieldType::Float(_) => "number".to_string(),
             FieldType::Boolean => "boolean".to_string(),
             FieldType::ID => "HelixID".to_string(),
+            FieldType::EntityID(name) if self.options.branded_ids => format!("{name}Id"),
+            FieldType::EntityID(_) => "HelixID".to_string(),
+            FieldType::Date => self.options.dates.typescript_type().to_string(),
             FieldType::Vector(dim) => format!("number[{dim}]"),
             FieldType::Array(inner) => {
-                format!("{}[]", Self::field_type_to_typescript(inner))
+                format!("{}[]", self.field_type_to_typescript(inner))
             }
             FieldType::Optional(inner) => {
-                format!("Optional<{}>", Self::field_type_to_typescript(inner))
+                format!("Optional<{}>", self.field_type_to_typescript(inner))
+            }
+            FieldType::Custom(name) => self
+                .options
+                .type_mappings
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.clone()),
+        }
+    }
+
+    fn return_type_to_typescript(&self, return_type: &ReturnType) -> String {
+        match return_type {
+            ReturnType::Node(name) | ReturnType::Vector(name) | ReturnType::Edge(name) => {
+                Self::type_name(name)
             }
-            FieldType::Custom(name) => name.clone(),
+            ReturnType::Scalar(field_type) => self.field_type_to_typescript(field_type),
+            ReturnType::Array(inner) => {
+                format!("{}[]", self.return_type_to_typescript(inner))
+            }
+            ReturnType::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "{}: {}",
+                            self.property_name(&field.name),
+                            self.return_type_to_typescript(&field.return_type)
+                        )
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            ReturnType::Unknown => "unknown".to_string(),
+        }
+    }
+
+    /// An interface member; optional types become `key?: T`, as do other
+    /// fields when `omittable`.
+    fn field(&self, name: &str, field_type: &FieldType, default: Option<&DefaultValue>) -> String {
+        let mut docs = Vec::new();
+        if let Some(default) = default {
+            docs.push(format!("@default {default}"));
+        }
+        if self.options.large_integers == LargeIntegerPolicy::Number
+            && let Some(integer_type) = wide_integer(field_type)
+        {
+            docs.push(format!(
+                "@remarks {integer_type:?}: values above 2^53 lose precision; see `--large-integers`."
+            ));
+        }
+        let mut output = match docs.as_slice() {
+            [] => String::new(),
+            [doc] => format!("  /** {doc} */\n"),
+            docs => format!(
+                "  /**\n{}   */\n",
+                docs.iter()
+                    .map(|doc| format!("   * {doc}\n"))
+                    .collect::<String>()
+            ),
+        };
+
+        let key = self.property_name(name);
+        output.push_str(&match field_type {
+            FieldType::Optional(inner) => {
+                format!("  {key}?: {};\n", self.field_type_to_typescript(inner))
+            }
+            _ if default.is_some() => {
+                format!("  {key}?: {};\n", self.field_type_to_typescript(field_type))
+            }
+            _ => format!("  {key}: {};\n", self.field_type_to_typescript(field_type)),
+        });
+        output
+    }
+
+    /// The declared name of a node, vector or edge.
+    fn type_name(name: &str) -> String {
+        Language::TypeScript.type_name(name)
+    }
+
+    /// The key for a schema property, parameter or result field in the
+    /// generated types.
+    fn property_name(&self, name: &str) -> String {
+        property_key(&self.options.property_naming.apply(name))
+    }
+}
+
+/// Quotes keys that are not valid identifiers (e.g. string literals in
+/// RETURN) or are keywords (`class`, `default`).
+pub(crate) fn property_key(name: &str) -> String {
+    let is_identifier = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_identifier && !Language::TypeScript.is_keyword(name) {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+/// Names declared by `export interface|type|const|function|class` lines, and
+/// whether each is type-only.
+fn module_exports(body: &str) -> Vec<(String, bool)> {
+    body.lines()
+        .filter_map(|line| {
+            let mut words = line.strip_prefix("export ")?.split_whitespace();
+            let is_type = match words.next()? {
+                "interface" | "type" => true,
+                "const" | "function" | "class" => false,
+                _ => return None,
+            };
+            let name: String = words
+                .next()?
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                .collect();
+            Some((name, is_type))
+        })
+        .collect()
+}
+
+/// Import specifier for module `to` from module `from`, both relative to the
+/// output directory, e.g. `nodes/User` -> `common` is `../common`.
+fn relative_specifier(from: &str, to: &str) -> String {
+    let depth = from.matches('/').count();
+    if depth == 0 {
+        format!("./{to}")
+    } else {
+        format!("{}{to}", "../".repeat(depth))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = "
+        N::User { Name: String, Age: U8 DEFAULT 0 }
+        N::Tag { name: String }
+    ";
+
+    /// The input type the params of the only query in `queries` extend.
+    fn input_type(queries: &str) -> Option<String> {
+        let schema = HelixQLSchemaParser::parse_sources(&[
+            (PathBuf::from("schema.hx"), SCHEMA.to_string()),
+            (PathBuf::from("queries.hx"), queries.to_string()),
+        ])
+        .unwrap();
+        let options = TypeScriptOptions::default();
+        let emitter = TypeScriptEmitter {
+            schema: &schema,
+            options: &options,
+        };
+        let query = schema.queries.values().next().unwrap();
+        emitter.params_input_type(query).map(|(name, _)| name)
+    }
+
+    #[test]
+    fn params_matching_properties_use_input_types() {
+        assert_eq!(
+            input_type(
+                "QUERY AddUser(Name: String, Age: U8?) =>\n u <- AddN<User>({Name: Name})\n RETURN u"
+            ),
+            Some("UserCreate".to_string())
+        );
+        assert_eq!(
+            input_type(
+                "QUERY EditUser(id: ID, Name: String?, Age: U8?) =>\n u <- N<User>(id)\n RETURN u"
+            ),
+            Some("UserUpdate".to_string())
+        );
+    }
+
+    #[test]
+    fn params_must_match_exactly() {
+        // A required param where the property has a default
+        assert_eq!(
+            input_type(
+                "QUERY AddUser(Name: String, Age: U8) =>\n u <- AddN<User>({Name: Name})\n RETURN u"
+            ),
+            None
+        );
+        // An extra param that is not a lookup of the entity
+        assert_eq!(
+            input_type(
+                "QUERY AddUser(Name: String, Age: U8?, limit: U32) =>\n u <- N<User>\n RETURN u"
+            ),
+            None
+        );
+        // An ID that is not used to look up a User
+        assert_eq!(
+            input_type(
+                "QUERY AddUser(id: ID, Name: String, Age: U8?) =>\n u <- N<User>\n RETURN u"
+            ),
+            None
+        );
+    }
+
+    /// The single-file output for `source` (schema and queries).
+    fn emit(source: &str, options: TypeScriptOptions) -> String {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+                .unwrap();
+        let emitter = TypeScriptEmitter {
+            schema: &schema,
+            options: &options,
+        };
+        emitter.check_names().unwrap();
+        emitter.generate()
+    }
+
+    const ACCOUNTS: &str = "N::Account { name: String, user_id: U128 }\n\
+        QUERY AddAccount(name: String, user_id: U128) =>\n\
+        a <- AddN<Account>({name: name, user_id: user_id})\n RETURN a";
+
+    fn generate_with(large_integers: LargeIntegerPolicy) -> String {
+        emit(
+            ACCOUNTS,
+            TypeScriptOptions {
+                large_integers,
+                client: ClientKind::Fetch,
+                ..TypeScriptOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn lossy_integers_are_documented() {
+        let output = generate_with(LargeIntegerPolicy::Number);
+        assert!(output.contains("@remarks U128: values above 2^53 lose precision"));
+        assert!(output.contains("(await response.json())"));
+        assert!(!output.contains("parseJson"));
+    }
+
+    #[test]
+    fn wide_integers_are_converted() {
+        let output = generate_with(LargeIntegerPolicy::Bigint);
+        assert!(output.contains("parseJson(await response.text())"));
+        assert!(output.contains("body: stringifyJson(params)"));
+        assert!(output.contains("user_id: ['user_id', reviveBigint]"));
+        assert!(!output.contains("@remarks"));
+
+        let output = generate_with(LargeIntegerPolicy::String);
+        assert!(output.contains("user_id: ['user_id', reviveIntegerString]"));
+        assert!(
+            output.contains("convertObject(params, { user_id: ['user_id', serializeInteger] })")
+        );
+    }
+
+    #[test]
+    fn helix_ts_client_requires_a_url_with_auth() {
+        let output = emit(ACCOUNTS, TypeScriptOptions::default());
+        assert!(output.contains("  const query = clientQuery(client, options);\n"));
+        assert!(output.contains(
+            "  if (options.url === undefined) {\n\
+             \x20   throw new TypeError('createTypedClient: `url` is required with `auth`"
+        ));
+        assert!(!output.contains("?? 'http://localhost"));
+    }
+
+    #[test]
+    fn exact_helix_ts_client_requires_a_url() {
+        let output = emit(
+            ACCOUNTS,
+            TypeScriptOptions {
+                large_integers: LargeIntegerPolicy::Bigint,
+                ..TypeScriptOptions::default()
+            },
+        );
+        assert!(output.contains("  url: string;\n"));
+        assert!(output.contains(
+            "export function createTypedClient(options: TypedClientOptions): TypedHelixDBClient {\n\
+             \x20 const query = authenticatedQuery(options.url, options.auth ?? {});\n"
+        ));
+        assert!(!output.contains("?? 'http://localhost"));
+    }
+
+    #[test]
+    fn convert_object_checks_entries() {
+        let output = emit(
+            ACCOUNTS,
+            TypeScriptOptions {
+                property_naming: NamingStrategy::Camel,
+                ..TypeScriptOptions::default()
+            },
+        );
+        // `fields[key]` may be undefined under `noUncheckedIndexedAccess`
+        assert!(output.contains(
+            "    const entry = Object.prototype.hasOwnProperty.call(fields, key) ? fields[key] : undefined;\n\
+             \x20   const [name, convert]: [string, Convert?] = entry ?? [key];\n"
+        ));
+        assert!(output.contains(
+            "  return convertObject(value, { created_at: ['createdAt'], updated_at: ['updatedAt'], user_id: ['userId'] });\n"
+        ));
+    }
+
+    /// A node named like another node's property, to catch imports taken
+    /// from property keys.
+    const NAMED: &str = "N::User { Name: String, Tag: ID }\n\
+        N::Name { value: String }\n\
+        E::Tagged { From: User, To: Name }\n\
+        QUERY GetUser(id: ID) =>\n u <- N<User>(id)\n RETURN u";
+
+    /// Each module's `import` and `export *` lines, by path.
+    fn module_headers(layout: Layout) -> Vec<(String, Vec<String>)> {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), NAMED.to_string())])
+                .unwrap();
+        let options = TypeScriptOptions {
+            layout,
+            ..TypeScriptOptions::default()
+        };
+        let emitter = TypeScriptEmitter {
+            schema: &schema,
+            options: &options,
+        };
+        emitter
+            .generate_modules()
+            .into_iter()
+            .map(|(path, contents)| {
+                let lines = contents
+                    .lines()
+                    .filter(|line| line.starts_with("import ") || line.starts_with("export *"))
+                    .map(String::from)
+                    .collect();
+                (path, lines)
+            })
+            .collect()
+    }
+
+    fn headers(modules: &[(String, Vec<String>)], path: &str) -> Vec<String> {
+        modules
+            .iter()
+            .find(|(module, _)| module == path)
+            .map(|(_, lines)| lines.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn split_modules_import_what_they_use() {
+        let modules = module_headers(Layout::Split);
+        let paths: Vec<&str> = modules.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "common", "nodes", "vectors", "edges", "queries", "client", "guards", "index"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "common"),
+            ["import HelixDB from 'helix-ts';"]
+        );
+        assert_eq!(
+            headers(&modules, "nodes"),
+            ["import type { HelixID, BaseNode } from './common';"]
+        );
+        assert_eq!(headers(&modules, "vectors"), Vec::<String>::new());
+        assert_eq!(
+            headers(&modules, "edges"),
+            [
+                "import type { BaseEdge } from './common';",
+                "import type { User, Name } from './nodes';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "queries"),
+            [
+                "import type { HelixID } from './common';",
+                "import type { User } from './nodes';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "client"),
+            [
+                "import HelixDB from 'helix-ts';",
+                "import type { HelixDBResponse, HelixDBInput } from './common';",
+                "import type { GetUserParams, GetUserResult } from './queries';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "guards"),
+            [
+                "import type { User, Name } from './nodes';",
+                "import type { Tagged } from './edges';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "index"),
+            [
+                "export * from './common';",
+                "export * from './nodes';",
+                "export * from './vectors';",
+                "export * from './edges';",
+                "export * from './queries';",
+                "export * from './client';",
+                "export * from './guards';"
+            ]
+        );
+    }
+
+    #[test]
+    fn per_entity_modules_import_what_they_use() {
+        let modules = module_headers(Layout::PerEntity);
+        assert_eq!(
+            headers(&modules, "nodes/User"),
+            ["import type { HelixID, BaseNode } from '../common';"]
+        );
+        assert_eq!(
+            headers(&modules, "nodes/Name"),
+            ["import type { BaseNode } from '../common';"]
+        );
+        assert_eq!(
+            headers(&modules, "edges/Tagged"),
+            [
+                "import type { BaseEdge } from '../common';",
+                "import type { User } from '../nodes/User';",
+                "import type { Name } from '../nodes/Name';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "nodes"),
+            [
+                "import type { User } from './nodes/User';",
+                "import type { Name } from './nodes/Name';",
+                "export * from './nodes/User';",
+                "export * from './nodes/Name';"
+            ]
+        );
+        assert_eq!(
+            headers(&modules, "queries"),
+            [
+                "import type { HelixID } from './common';",
+                "import type { User } from './nodes/User';"
+            ]
+        );
+        // Entity modules are only re-exported through their group
+        assert_eq!(
+            headers(&modules, "index"),
+            [
+                "export * from './common';",
+                "export * from './nodes';",
+                "export * from './vectors';",
+                "export * from './edges';",
+                "export * from './queries';",
+                "export * from './client';",
+                "export * from './guards';"
+            ]
+        );
+    }
+
+    fn check(source: &str, options: TypeScriptOptions) -> Result<()> {
+        let schema =
+            HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+                .unwrap();
+        TypeScriptEmitter {
+            schema: &schema,
+            options: &options,
         }
+        .check_names()
+    }
+
+    #[test]
+    fn derived_names_collide() {
+        let source = "N::User { name: String }\nN::UserId { name: String }";
+        assert!(check(source, TypeScriptOptions::default()).is_ok());
+        let branded = TypeScriptOptions {
+            branded_ids: true,
+            ..TypeScriptOptions::default()
+        };
+        assert_eq!(
+            check(source, branded).unwrap_err().to_string(),
+            "node `User` and node `UserId` both generate `UserId` in TypeScript declarations"
+        );
+
+        let source = "N::User { name: String }\nN::UserSchema { name: String }";
+        let zod = TypeScriptOptions {
+            zod: true,
+            ..TypeScriptOptions::default()
+        };
+        assert!(check(source, zod).is_err());
+
+        // `isUser` guards a `User`
+        let source = "N::User { name: String }\nN::isUser { name: String }";
+        assert!(check(source, TypeScriptOptions::default()).is_err());
+
+        let source = "N::User { name: String }\nN::UserCreate { name: String }";
+        assert!(check(source, TypeScriptOptions::default()).is_err());
+    }
+
+    #[test]
+    fn single_property_entities_are_not_matched() {
+        assert_eq!(
+            input_type("QUERY FindUsersByTag(name: String) =>\n t <- N<Tag>\n RETURN t"),
+            None
+        );
+    }
+
+    const FOLLOWS: &str = "N::User { name: String, Age: U8, Tags: [String], Nick: String?, Joined: Date }\n\
+        V::Doc { content: String }\n\
+        E::Follows { From: User, To: User, Properties: { Since: Date? } }\n\
+        QUERY getUser(id: ID, since: Date) =>\n\
+        \x20   u <- N<User>(id)\n\
+        \x20   RETURN u";
+
+    fn fetch_options() -> TypeScriptOptions {
+        TypeScriptOptions {
+            client: ClientKind::Fetch,
+            ..TypeScriptOptions::default()
+        }
+    }
+
+    #[test]
+    fn fetch_client_has_no_dependencies() {
+        let output = emit(FOLLOWS, fetch_options());
+        assert!(!output.contains("import "));
+        assert!(!output.contains("ReturnType<HelixDB"));
+        assert!(output.contains(
+            "  getUser(params: GetUserParams, request?: RequestOptions): Promise<GetUserResult>;\n"
+        ));
+        assert!(output.contains(
+            "  const baseUrl = (options.baseUrl ?? 'http://localhost:6969').replace(/\\/+$/, '');\n\
+             \x20 const fetchImpl = options.fetch ?? globalThis.fetch;\n"
+        ));
+        assert!(output.contains(
+            "    const response = await fetchImpl(`${baseUrl}/${endpoint}`, {\n\
+             \x20     method: 'POST',\n"
+        ));
+        assert!(output.contains(
+            "      return (await query('getUser', params, request)) as GetUserResult;\n"
+        ));
+    }
+
+    #[test]
+    fn fetch_client_merges_headers_and_signals() {
+        let output = emit(FOLLOWS, fetch_options());
+        // Per-request headers win over client headers and credentials
+        assert!(output.contains(
+            "        'Content-Type': 'application/json',\n\
+             \x20       ...options.headers,\n\
+             \x20       ...helixAuthHeaders(options.auth),\n\
+             \x20       ...request.headers,\n"
+        ));
+        assert!(output.contains("    const timeoutMs = request.timeoutMs ?? options.timeoutMs;\n"));
+        assert!(output.contains(
+            "    if (timeoutMs !== undefined) signals.push(AbortSignal.timeout(timeoutMs));\n"
+        ));
+        assert!(output.contains(
+            "      signal: signals.length > 1 ? AbortSignal.any(signals) : signals[0],\n"
+        ));
+        assert!(output.contains(
+            "      throw new HelixDBError(endpoint, response.status, await response.text());\n"
+        ));
+    }
+
+    #[test]
+    fn guards_check_every_field() {
+        let mut schema = HelixQLSchemaParser::parse_sources(&[(
+            PathBuf::from("schema.hx"),
+            FOLLOWS.to_string(),
+        )])
+        .unwrap();
+        // Vector dimensions only come from introspection
+        schema.vectors["Doc"]
+            .properties
+            .insert("embedding".to_string(), FieldType::Vector(3));
+        let options = TypeScriptOptions::default();
+        let output = TypeScriptEmitter {
+            schema: &schema,
+            options: &options,
+        }
+        .generate();
+        assert!(output.contains(
+            "function validateUser(value: unknown, path: string): string | undefined {\n\
+             \x20 return checkFields(value, path, {\n\
+             \x20   id: checkString,\n\
+             \x20   created_at: checkOptional(checkString),\n\
+             \x20   updated_at: checkOptional(checkString),\n\
+             \x20   name: checkString,\n\
+             \x20   Age: checkInteger,\n\
+             \x20   Tags: checkArray(checkString),\n\
+             \x20   Nick: checkOptional(checkString),\n\
+             \x20   Joined: checkString,\n\
+             \x20 });\n"
+        ));
+        assert!(output.contains("    embedding: checkArray(checkNumber, 3),\n"));
+        // Edges check their endpoints as nested entities
+        assert!(output.contains("    from: validateUser,\n    to: validateUser,\n"));
+        for name in ["User", "Doc", "Follows"] {
+            assert!(output.contains(&format!(
+                "export function is{name}(value: unknown): value is {name} {{\n\
+                 \x20 return validate{name}(value, '{name}') === undefined;\n"
+            )));
+        }
+        assert!(!output.contains("export function assert"));
+    }
+
+    #[test]
+    fn assertions_throw_on_the_first_mismatch() {
+        let output = emit(
+            FOLLOWS,
+            TypeScriptOptions {
+                assertions: true,
+                ..TypeScriptOptions::default()
+            },
+        );
+        assert!(output.contains(
+            "export function assertFollows(value: unknown): asserts value is Follows {\n\
+             \x20 const error = validateFollows(value, 'Follows');\n\
+             \x20 if (error) throw new TypeError(error);\n\
+             }\n"
+        ));
+        assert!(output.contains(
+            "      const error = check(value[i], `${path}[${i}]`);\n\
+             \x20     if (error) return error;\n"
+        ));
+    }
+
+    #[test]
+    fn guards_follow_the_generated_types() {
+        let output = emit(
+            "N::Account { Name: String, Balance: U128 }",
+            TypeScriptOptions {
+                large_integers: LargeIntegerPolicy::Bigint,
+                property_naming: NamingStrategy::Camel,
+                ..TypeScriptOptions::default()
+            },
+        );
+        assert!(output.contains("    name: checkString,\n    balance: checkBigint,\n"));
+    }
+
+    fn dates(dates: DateRepresentation) -> String {
+        emit(
+            FOLLOWS,
+            TypeScriptOptions {
+                dates,
+                ..TypeScriptOptions::default()
+            },
+        )
+    }
+
+    #[test]
+    fn iso_dates_are_sent_as_is() {
+        let output = dates(DateRepresentation::IsoString);
+        assert!(output.contains("  created_at?: string;\n  updated_at?: string;\n"));
+        assert!(output.contains("  Joined: string;\n"));
+        assert!(output.contains("  since: string;\n"));
+        assert!(!output.contains("convertObject"));
+        assert!(output.contains("    Joined: checkString,\n"));
+    }
+
+    #[test]
+    fn date_objects_are_revived_and_serialized() {
+        let output = dates(DateRepresentation::Date);
+        assert!(output.contains("  created_at?: Date;\n  updated_at?: Date;\n"));
+        assert!(output.contains("  Joined: Date;\n"));
+        assert!(output.contains("  Since?: Date;\n"));
+        assert!(output.contains(
+            "  return typeof value === 'string' || typeof value === 'number' ? new Date(value) : value;\n"
+        ));
+        assert!(output.contains(
+            "export function decodeUser(value: any): User {\n\
+             \x20 return convertObject(value, { created_at: ['created_at', reviveDate], updated_at: ['updated_at', reviveDate], Joined: ['Joined', reviveDate] });\n"
+        ));
+        // Edges revive their endpoints too
+        assert!(output.contains(
+            "from: ['from', decodeUser], to: ['to', decodeUser], Since: ['Since', reviveDate]"
+        ));
+        assert!(output.contains(
+            "convertObject(await query('getUser', convertObject(params, { since: ['since', serializeDate] })), { u: ['u', decodeUser] })"
+        ));
+        assert!(output.contains("    Joined: checkDate,\n"));
+    }
+
+    #[test]
+    fn epoch_dates_are_parsed_and_serialized() {
+        let output = dates(DateRepresentation::Epoch);
+        assert!(output.contains("  created_at?: number;\n  updated_at?: number;\n"));
+        assert!(output.contains("  Joined: number;\n"));
+        assert!(output.contains(
+            "function parseEpoch(value: any): any {\n\
+             \x20 return typeof value === 'string' ? Date.parse(value) : value;\n"
+        ));
+        assert!(output.contains("Joined: ['Joined', parseEpoch]"));
+        assert!(!output.contains("reviveDate"));
+        // Params go out as RFC 3339 strings, like with `Date` objects
+        assert!(output.contains(
+            "  return value instanceof Date || typeof value === 'number' ? new Date(value).toISOString() : value;\n"
+        ));
+        assert!(output.contains("convertObject(params, { since: ['since', serializeDate] })"));
+        assert!(output.contains("    Joined: checkNumber,\n"));
+    }
+
+    #[test]
+    fn date_arrays_convert_each_item() {
+        let output = emit(
+            "N::Event { At: [Date], When: Date? }",
+            TypeScriptOptions {
+                dates: DateRepresentation::Epoch,
+                ..TypeScriptOptions::default()
+            },
+        );
+        assert!(
+            output.contains("At: ['At', convertArray(parseEpoch)], When: ['When', parseEpoch]")
+        );
     }
 }
@@ -0,0 +1,326 @@
+use crate::{
+    identifier::{NamingStrategy, to_pascal_case},
+    schema::*,
+    ts_generator::{DateRepresentation, LargeIntegerPolicy, property_key},
+};
+use indexmap::IndexMap;
+
+/// Emits Zod schemas mirroring the TypeScript interfaces, for runtime
+/// validation of entities and query parameters.
+pub struct ZodGenerator<'a> {
+    schema: &'a HelixSchema,
+    large_integers: LargeIntegerPolicy,
+    branded_ids: bool,
+    type_mappings: Option<&'a IndexMap<String, String>>,
+    property_naming: NamingStrategy,
+    dates: DateRepresentation,
+}
+
+impl<'a> ZodGenerator<'a> {
+    pub fn new(schema: &'a HelixSchema) -> Self {
+        Self {
+            schema,
+            large_integers: LargeIntegerPolicy::default(),
+            branded_ids: false,
+            type_mappings: None,
+            property_naming: NamingStrategy::default(),
+            dates: DateRepresentation::default(),
+        }
+    }
+
+    /// Must match the policy used for the TypeScript interfaces.
+    pub fn with_large_integers(mut self, large_integers: LargeIntegerPolicy) -> Self {
+        self.large_integers = large_integers;
+        self
+    }
+
+    /// Must match `branded_ids` used for the TypeScript interfaces.
+    pub fn with_branded_ids(mut self, branded_ids: bool) -> Self {
+        self.branded_ids = branded_ids;
+        self
+    }
+
+    /// Custom types mapped to a TypeScript type are checked at the type
+    /// level only (`z.custom<T>()`).
+    pub fn with_type_mappings(mut self, type_mappings: &'a IndexMap<String, String>) -> Self {
+        self.type_mappings = Some(type_mappings);
+        self
+    }
+
+    /// Must match `property_naming` used for the TypeScript interfaces.
+    pub fn with_property_naming(mut self, property_naming: NamingStrategy) -> Self {
+        self.property_naming = property_naming;
+        self
+    }
+
+    /// Must match `dates` used for the TypeScript interfaces.
+    pub fn with_dates(mut self, dates: DateRepresentation) -> Self {
+        self.dates = dates;
+        self
+    }
+
+    pub fn generate(&self) -> String {
+        let mut output = String::new();
+        output.push_str("// Zod schemas for runtime validation\n");
+        output.push_str("export const HelixIDSchema = z.string().uuid();\n\n");
+
+        if self.branded_ids {
+            let entities = self
+                .schema
+                .nodes
+                .keys()
+                .chain(self.schema.vectors.keys())
+                .chain(self.schema.edges.keys());
+            for name in entities {
+                output.push_str(&format!(
+                    "export const {name}IdSchema = HelixIDSchema.transform((id) => id as {name}Id);\n"
+                ));
+            }
+            output.push('\n');
+        }
+
+        for (name, node) in &self.schema.nodes {
+            output.push_str(&self.object_schema(
+                name,
+                &[
+                    ("id", self.id_schema(name)),
+                    ("created_at", self.timestamp_schema()),
+                    ("updated_at", self.timestamp_schema()),
+                ],
+                &node.properties,
+            ));
+        }
+
+        for (name, vector) in &self.schema.vectors {
+            output.push_str(&self.object_schema(
+                name,
+                &[("id", self.id_schema(name))],
+                &vector.properties,
+            ));
+        }
+
+        for (name, edge) in &self.schema.edges {
+            output.push_str(&self.object_schema(
+                name,
+                &[
+                    ("id", format!("{}.optional()", self.id_schema(name))),
+                    ("created_at", self.timestamp_schema()),
+                    ("from", format!("{}Schema", edge.from_node)),
+                    ("to", format!("{}Schema", edge.to_node)),
+                ],
+                &edge.properties,
+            ));
+        }
+
+        for (name, query) in &self.schema.queries {
+            let params_name = format!("{}Params", to_pascal_case(name));
+            output.push_str(&self.object_schema(&params_name, &[], &query.parameters));
+        }
+
+        output
+    }
+
+    fn object_schema(
+        &self,
+        name: &str,
+        base_fields: &[(&str, String)],
+        properties: &IndexMap<String, FieldType>,
+    ) -> String {
+        let mut output = format!("export const {name}Schema = z.object({{\n");
+
+        // Declared properties take precedence over the built-in fields
+        for (field_name, zod_type) in base_fields {
+            if properties.contains_key(*field_name) {
+                continue;
+            }
+            let key = property_key(&self.property_naming.apply(field_name));
+            output.push_str(&format!("  {key}: {zod_type},\n"));
+        }
+
+        for (prop_name, field_type) in properties {
+            let zod_type = self.field_type_to_zod(field_type);
+            let key = property_key(&self.property_naming.apply(prop_name));
+            output.push_str(&format!("  {key}: {zod_type},\n"));
+        }
+
+        output.push_str("});\n\n");
+        output
+    }
+
+    fn field_type_to_zod(&self, field_type: &FieldType) -> String {
+        match field_type {
+            FieldType::String => "z.string()".to_string(),
+            FieldType::Integer(integer_type) => self.integer_to_zod(*integer_type),
+            FieldType::Float(_) => "z.number()".to_string(),
+            FieldType::Boolean => "z.boolean()".to_string(),
+            FieldType::ID => "HelixIDSchema".to_string(),
+            FieldType::EntityID(name) => self.id_schema(name),
+            FieldType::Date => match self.dates {
+                DateRepresentation::IsoString => "z.string().datetime({ offset: true })",
+                DateRepresentation::Date => "z.date()",
+                DateRepresentation::Epoch => "z.number()",
+            }
+            .to_string(),
+            FieldType::Vector(dim) => format!("z.array(z.number()).length({dim})"),
+            FieldType::Array(inner) => {
+                format!("z.array({})", self.field_type_to_zod(inner))
+            }
+            FieldType::Optional(inner) => {
+                format!("{}.nullish()", self.field_type_to_zod(inner))
+            }
+            FieldType::Custom(name) => match self.type_mappings.and_then(|m| m.get(name)) {
+                Some(ts_type) => format!("z.custom<{ts_type}>()"),
+                None => "z.unknown()".to_string(),
+            },
+        }
+    }
+
+    fn timestamp_schema(&self) -> String {
+        format!("{}.optional()", self.field_type_to_zod(&FieldType::Date))
+    }
+
+    fn id_schema(&self, entity: &str) -> String {
+        if self.branded_ids {
+            format!("{entity}IdSchema")
+        } else {
+            "HelixIDSchema".to_string()
+        }
+    }
+
+    fn integer_to_zod(&self, integer_type: IntegerType) -> String {
+        if let Some((min, max)) = integer_type.range() {
+            return format!("z.number().int().min({min}).max({max})");
+        }
+        let unsigned = !integer_type.is_signed();
+        match self.large_integers.typescript_type(integer_type) {
+            "bigint" if unsigned => "z.bigint().nonnegative()".to_string(),
+            "bigint" => "z.bigint()".to_string(),
+            "string" if unsigned => r"z.string().regex(/^\d+$/)".to_string(),
+            "string" => r"z.string().regex(/^-?\d+$/)".to_string(),
+            _ if unsigned => "z.number().int().nonnegative()".to_string(),
+            _ => "z.number().int()".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::HelixQLSchemaParser;
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = "
+        N::User { name: String, Age: U8, Score: I32, Big: U64, Ratio: F32, Tags: [String], Nick: String?, Joined: Date }
+        V::Doc { content: String }
+        E::Follows { From: User, To: User, Properties: { Since: Date } }
+        QUERY getUser(id: ID, Nick: String?) =>
+            u <- N<User>(id)
+            RETURN u
+    ";
+
+    fn schema(source: &str) -> HelixSchema {
+        HelixQLSchemaParser::parse_sources(&[(PathBuf::from("schema.hx"), source.to_string())])
+            .unwrap()
+    }
+
+    #[test]
+    fn schemas_mirror_entities_and_params() {
+        let schema = schema(SCHEMA);
+        let output = ZodGenerator::new(&schema).generate();
+        assert!(output.contains("export const HelixIDSchema = z.string().uuid();\n"));
+        assert!(output.contains(
+            "export const UserSchema = z.object({\n\
+             \x20 id: HelixIDSchema,\n\
+             \x20 created_at: z.string().datetime({ offset: true }).optional(),\n\
+             \x20 updated_at: z.string().datetime({ offset: true }).optional(),\n\
+             \x20 name: z.string(),\n"
+        ));
+        assert!(output.contains("  Tags: z.array(z.string()),\n  Nick: z.string().nullish(),\n"));
+        assert!(output.contains(
+            "export const DocSchema = z.object({\n  id: HelixIDSchema,\n  content: z.string(),\n});\n"
+        ));
+        assert!(output.contains(
+            "  id: HelixIDSchema.optional(),\n\
+             \x20 created_at: z.string().datetime({ offset: true }).optional(),\n\
+             \x20 from: UserSchema,\n\
+             \x20 to: UserSchema,\n"
+        ));
+        assert!(output.contains(
+            "export const GetUserParamsSchema = z.object({\n\
+             \x20 id: HelixIDSchema,\n\
+             \x20 Nick: z.string().nullish(),\n\
+             });\n"
+        ));
+    }
+
+    #[test]
+    fn numbers_are_refined_to_their_range() {
+        let schema = schema(SCHEMA);
+        let output = ZodGenerator::new(&schema).generate();
+        assert!(output.contains("  Age: z.number().int().min(0).max(255),\n"));
+        assert!(output.contains("  Score: z.number().int().min(-2147483648).max(2147483647),\n"));
+        assert!(output.contains("  Big: z.number().int().nonnegative(),\n"));
+        // Floats have no bounds worth checking
+        assert!(output.contains("  Ratio: z.number(),\n"));
+    }
+
+    #[test]
+    fn large_integers_follow_the_policy() {
+        let schema = schema(SCHEMA);
+        let zod = |policy| {
+            ZodGenerator::new(&schema)
+                .with_large_integers(policy)
+                .generate()
+        };
+        assert!(zod(LargeIntegerPolicy::Bigint).contains("  Big: z.bigint().nonnegative(),\n"));
+        assert!(zod(LargeIntegerPolicy::String).contains("  Big: z.string().regex(/^\\d+$/),\n"));
+        // Narrow integers keep their range under every policy
+        assert!(
+            zod(LargeIntegerPolicy::Bigint).contains("  Age: z.number().int().min(0).max(255),\n")
+        );
+
+        let signed = self::schema("N::Counter { total: I64 }");
+        let output = ZodGenerator::new(&signed)
+            .with_large_integers(LargeIntegerPolicy::String)
+            .generate();
+        assert!(output.contains("  total: z.string().regex(/^-?\\d+$/),\n"));
+    }
+
+    #[test]
+    fn dates_follow_the_representation() {
+        let schema = schema(SCHEMA);
+        let zod = |dates| ZodGenerator::new(&schema).with_dates(dates).generate();
+        assert!(zod(DateRepresentation::Date).contains("  Joined: z.date(),\n"));
+        assert!(zod(DateRepresentation::Date).contains("  created_at: z.date().optional(),\n"));
+        assert!(zod(DateRepresentation::Epoch).contains("  Since: z.number(),\n"));
+    }
+
+    #[test]
+    fn branded_ids_transform_each_entity_id() {
+        let schema = schema(SCHEMA);
+        let output = ZodGenerator::new(&schema).with_branded_ids(true).generate();
+        assert!(output.contains(
+            "export const UserIdSchema = HelixIDSchema.transform((id) => id as UserId);\n"
+        ));
+        assert!(output.contains(
+            "export const FollowsIdSchema = HelixIDSchema.transform((id) => id as FollowsId);\n"
+        ));
+        assert!(output.contains("  id: UserIdSchema,\n"));
+        assert!(output.contains("  id: FollowsIdSchema.optional(),\n"));
+    }
+
+    #[test]
+    fn property_naming_and_type_mappings_apply_together() {
+        let schema = schema("N::Place { PlaceName: String, at: Point, area: Polygon }");
+        let mappings = IndexMap::from([("Point".to_string(), "[number, number]".to_string())]);
+        let output = ZodGenerator::new(&schema)
+            .with_property_naming(NamingStrategy::Snake)
+            .with_type_mappings(&mappings)
+            .generate();
+        assert!(output.contains(
+            "  place_name: z.string(),\n\
+             \x20 at: z.custom<[number, number]>(),\n\
+             \x20 area: z.unknown(),\n"
+        ));
+    }
+}